@@ -0,0 +1,900 @@
+use std::{collections::BTreeSet, fmt};
+
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, Local, Months, NaiveDate, NaiveDateTime, TimeZone,
+};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Value, json};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    checkin::{CheckIn, Streak},
+    cli::{ProgressBar, WFetchArgs},
+    term_color,
+};
+
+/// parses a UNIX timestamp in seconds, or an ISO 8601 date or datetime, dates and datetimes
+/// without a timezone are in local time
+pub fn parse_date(start: &str) -> Result<DateTime<FixedOffset>, String> {
+    let start = start.trim();
+
+    if let Ok(timestamp) = start.parse::<i64>() {
+        return DateTime::from_timestamp(timestamp, 0)
+            .map(|start| start.fixed_offset())
+            .ok_or_else(|| format!("timestamp `{start}` is out of range"));
+    }
+
+    if let Ok(start) = DateTime::parse_from_rfc3339(start) {
+        return Ok(start);
+    }
+
+    let naive = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(start, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(start, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })
+    .ok_or_else(|| {
+        format!(
+            "invalid date `{start}`, expected an ISO 8601 date or datetime, or a UNIX timestamp"
+        )
+    })?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|start| start.fixed_offset())
+        .ok_or_else(|| format!("date `{start}` does not exist in the local timezone"))
+}
+
+/// the current time, or the time from --now for reproducible output
+pub fn now(args: &WFetchArgs) -> Result<DateTime<FixedOffset>, String> {
+    args.now
+        .as_deref()
+        .map_or_else(|| Ok(Local::now().fixed_offset()), parse_date)
+}
+
+/// date in the config, either a UNIX timestamp, a string or a TOML datetime
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum DateValue {
+    Timestamp(i64),
+    Text(String),
+    Datetime(toml::value::Datetime),
+}
+
+impl fmt::Display for DateValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Timestamp(timestamp) => write!(f, "{timestamp}"),
+            Self::Text(text) => write!(f, "{text}"),
+            Self::Datetime(datetime) => write!(f, "{datetime}"),
+        }
+    }
+}
+
+pub fn deserialize_date<'de, D>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse_date(&DateValue::deserialize(deserializer)?.to_string()).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_end<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_date(deserializer).map(Some)
+}
+
+fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse_duration(&String::deserialize(deserializer)?)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// length of a challenge, months are added before weeks and days
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Duration {
+    pub years: u32,
+    pub months: u32,
+    pub weeks: u32,
+    pub days: u32,
+}
+
+impl Duration {
+    /// the duration repeated the given number of times, None if it overflows
+    fn times(self, n: u32) -> Option<Self> {
+        Some(Self {
+            years: self.years.checked_mul(n)?,
+            months: self.months.checked_mul(n)?,
+            weeks: self.weeks.checked_mul(n)?,
+            days: self.days.checked_mul(n)?,
+        })
+    }
+
+    /// adds the duration to the date, None if the result is out of range
+    fn add_to(self, date: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        let months = self.years.checked_mul(12)?.checked_add(self.months)?;
+        let days = u64::from(self.weeks) * 7 + u64::from(self.days);
+
+        date.checked_add_months(Months::new(months))
+            .and_then(|date| date.checked_add_days(Days::new(days)))
+    }
+
+    /// human readable duration, e.g. "1 Year 6 Months" or "100 Days"
+    fn label(self) -> String {
+        let months = u64::from(self.years) * 12 + u64::from(self.months);
+
+        [
+            (months / 12, "Year"),
+            (months % 12, "Month"),
+            (u64::from(self.weeks), "Week"),
+            (u64::from(self.days), "Day"),
+        ]
+        .iter()
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| plural((*value).cast_signed(), unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+/// calendar days from today until the date in local time, negative if the date has passed
+pub fn days_until(date: DateTime<FixedOffset>, today: NaiveDate) -> i64 {
+    (date.with_timezone(&Local).date_naive() - today).num_days()
+}
+
+pub fn plural(value: i64, unit: &str) -> String {
+    if value == 1 {
+        format!("{value} {unit}")
+    } else {
+        format!("{value} {unit}s")
+    }
+}
+
+fn deserialize_milestones<'de, D>(deserializer: D) -> Result<Vec<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|milestone| parse_duration(milestone).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// parses a duration made up of years, months, weeks and days, e.g. 100d, 12w or 1y6m
+pub fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration `{duration}`, expected e.g. 100d, 12w or 1y6m");
+
+    let mut parsed = Duration::default();
+    let mut number = String::new();
+    for c in duration.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let value: u32 = number.parse().map_err(|_| invalid())?;
+        let unit = match c.to_ascii_lowercase() {
+            'y' => &mut parsed.years,
+            'm' => &mut parsed.months,
+            'w' => &mut parsed.weeks,
+            'd' => &mut parsed.days,
+            _ => return Err(invalid()),
+        };
+        *unit = unit.checked_add(value).ok_or_else(invalid)?;
+        number.clear();
+    }
+
+    // trailing number without a unit
+    if !number.is_empty() || parsed == Duration::default() {
+        return Err(invalid());
+    }
+
+    Ok(parsed)
+}
+
+/// weeks in the heatmap of challenges that are longer, about a year like github
+const HEATMAP_MAX_WEEKS: u64 = 53;
+
+/// colors of the heatmap cells as ansi color codes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeatmapColors {
+    pub checkin: String,
+    pub passed: String,
+    pub upcoming: String,
+}
+
+impl Default for HeatmapColors {
+    fn default() -> Self {
+        Self {
+            checkin: "32".to_string(),
+            passed: "2;32".to_string(),
+            upcoming: "90".to_string(),
+        }
+    }
+}
+
+/// heatmap of the days of the challenge, or of the last weeks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    pub weeks: Option<u32>,
+    pub colors: HeatmapColors,
+}
+
+/// progress of a challenge relative to today
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    NotStarted { days: i64 },
+    InProgress,
+    Completed { days: i64 },
+}
+
+/// a challenge with a start and a duration, from the command line or the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Challenge {
+    /// replaces the title generated from the duration and type
+    pub title: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub start: DateTime<FixedOffset>,
+    #[serde(default)]
+    pub years: u32,
+    #[serde(default)]
+    pub months: u32,
+    /// instead of the years and months
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub duration: Option<Duration>,
+    /// instead of the duration
+    #[serde(default, deserialize_with = "deserialize_end")]
+    pub end: Option<DateTime<FixedOffset>>,
+    /// repeating intervals from the start, e.g. every 100 days or every year
+    #[serde(default, deserialize_with = "deserialize_milestones")]
+    pub milestones: Vec<Duration>,
+}
+
+impl Challenge {
+    pub fn from_args(args: &WFetchArgs) -> Result<Self, String> {
+        // a challenge without a length lasts 10 years
+        let has_length = args.challenge_months.is_some()
+            || args.challenge_duration.is_some()
+            || args.challenge_end.is_some();
+
+        Self {
+            title: None,
+            kind: args.challenge_type.clone(),
+            start: parse_date(&args.challenge_start)?,
+            years: args
+                .challenge_years
+                .unwrap_or(if has_length { 0 } else { 10 }),
+            months: args.challenge_months.unwrap_or_default(),
+            duration: args
+                .challenge_duration
+                .as_deref()
+                .map(parse_duration)
+                .transpose()?,
+            end: args.challenge_end.as_deref().map(parse_date).transpose()?,
+            milestones: args
+                .challenge_milestones
+                .iter()
+                .map(|milestone| parse_duration(milestone))
+                .collect::<Result<_, _>>()?,
+        }
+        .validate()
+    }
+
+    /// checks that the challenge has a single duration and ends after it starts
+    pub fn validate(self) -> Result<Self, String> {
+        let name = self
+            .name()
+            .map_or_else(|| "the challenge".to_string(), |name| format!("`{name}`"));
+
+        let has_years = self.years > 0 || self.months > 0;
+        if self.end.is_some() && (self.duration.is_some() || has_years) {
+            return Err(format!(
+                "{name} can only have one of an end, a duration, or years and months"
+            ));
+        }
+        if self.duration.is_some() && has_years {
+            return Err(format!(
+                "{name} can only have one of a duration, or years and months"
+            ));
+        }
+
+        let end = match self.end {
+            Some(end) => end,
+            None => self
+                .duration()
+                .add_to(self.start)
+                .ok_or_else(|| format!("the end of {name} is out of range"))?,
+        };
+
+        if end <= self.start {
+            return Err(format!("{name} must end after it starts"));
+        }
+
+        Ok(self)
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration.unwrap_or_else(|| Duration {
+            years: self.years,
+            months: self.months,
+            ..Duration::default()
+        })
+    }
+
+    /// the end is checked when the challenge is validated
+    fn end(&self) -> DateTime<FixedOffset> {
+        self.end
+            .or_else(|| self.duration().add_to(self.start))
+            .unwrap_or(self.start)
+    }
+
+    fn state(&self, now: DateTime<FixedOffset>) -> State {
+        let today = now.with_timezone(&Local).date_naive();
+
+        if now < self.start {
+            State::NotStarted {
+                days: days_until(self.start, today),
+            }
+        } else if now >= self.end() {
+            State::Completed {
+                days: -days_until(self.end(), today),
+            }
+        } else {
+            State::InProgress
+        }
+    }
+
+    /// color of the title and progress bar
+    fn color(&self, now: DateTime<FixedOffset>) -> i32 {
+        if matches!(self.state(now), State::Completed { .. }) {
+            2
+        } else {
+            3
+        }
+    }
+
+    /// elapsed and total seconds of the challenge
+    fn elapsed(&self, now: DateTime<FixedOffset>) -> (i64, i64) {
+        (
+            now.timestamp() - self.start.timestamp(),
+            self.end().timestamp() - self.start.timestamp(),
+        )
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn text(&self, now: DateTime<FixedOffset>) -> String {
+        match self.state(now) {
+            State::NotStarted { days: 0 } => return "Starts today".to_string(),
+            State::NotStarted { days } => return format!("Starts in {}", plural(days, "Day")),
+            State::Completed { days: 0 } => return "Completed today".to_string(),
+            State::Completed { days } => {
+                return format!("Completed {} ago", plural(days, "Day"));
+            }
+            State::InProgress => {}
+        }
+
+        let (elapsed, total) = self.elapsed(now);
+
+        let percent = elapsed as f32 / total as f32 * 100.0;
+
+        let elapsed_days = elapsed / 60 / 60 / 24;
+        let total_days = total / 60 / 60 / 24;
+
+        format!("{elapsed_days} Days / {total_days} Days ({percent:.2}%)")
+    }
+
+    fn title(&self, icon: &str, now: DateTime<FixedOffset>) -> String {
+        let title = self.title.clone().unwrap_or_else(|| {
+            let mut segments: Vec<String> = Vec::new();
+            if self.end.is_some() {
+                let days = (self.end() - self.start).num_days();
+                segments.push(format!("{days} DAY"));
+            } else {
+                let duration = self.duration();
+                for (value, unit) in [
+                    (duration.years, "YEAR"),
+                    (duration.months, "MONTH"),
+                    (duration.weeks, "WEEK"),
+                    (duration.days, "DAY"),
+                ] {
+                    if value != 0 {
+                        segments.push(format!("{value} {unit}"));
+                    }
+                }
+            }
+
+            segments.push(match &self.kind {
+                None => String::new(),
+                Some(t) => t.to_owned().to_uppercase(),
+            });
+
+            segments.push("CHALLENGE".to_string());
+
+            if matches!(self.state(now), State::Completed { .. }) {
+                segments.push("COMPLETED!".to_string());
+            }
+
+            segments
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+
+        if icon.is_empty() {
+            format!("  {title}  ")
+        } else {
+            format!("{icon}  {title}  {icon}")
+        }
+    }
+
+    /// name used to match check-ins to the challenge
+    pub fn name(&self) -> Option<&str> {
+        self.title.as_deref().or(self.kind.as_deref())
+    }
+
+    /// the title and type, either of which can be used to check in to the challenge
+    pub fn names(&self) -> Vec<&str> {
+        [self.title.as_deref(), self.kind.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// true if the name is the title or type of the challenge
+    pub fn is_named(&self, name: &str) -> bool {
+        self.names().iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// days with a check-in during the challenge, up to today
+    fn checkin_days(
+        &self,
+        checkins: &[CheckIn],
+        now: DateTime<FixedOffset>,
+    ) -> BTreeSet<NaiveDate> {
+        let today = now.with_timezone(&Local).date_naive();
+        let start = self.start.with_timezone(&Local).date_naive();
+        // the end of the challenge is exclusive
+        let end = self.end().with_timezone(&Local).date_naive();
+        checkins
+            .iter()
+            .filter(|checkin| checkin.is_for(&self.names()))
+            .map(|checkin| checkin.time.date_naive())
+            .filter(|day| (start..end).contains(day) && *day <= today)
+            .collect()
+    }
+
+    /// streaks from the check-ins since the start of the challenge
+    fn streak(&self, checkins: &[CheckIn], now: DateTime<FixedOffset>) -> Option<Streak> {
+        Streak::new(
+            &self.checkin_days(checkins, now),
+            now.with_timezone(&Local).date_naive(),
+        )
+    }
+
+    /// rows of the heatmap from monday to sunday with a column per week, and the number of weeks
+    fn heatmap(
+        &self,
+        checkins: &[CheckIn],
+        heatmap: &Heatmap,
+        now: DateTime<FixedOffset>,
+    ) -> (usize, Vec<String>) {
+        let today = now.with_timezone(&Local).date_naive();
+        let start = self.start.with_timezone(&Local).date_naive();
+        // the end of the challenge is exclusive
+        let end = self.end().with_timezone(&Local).date_naive();
+        let checkin_days = self.checkin_days(checkins, now);
+
+        let before = |date: NaiveDate, days: u64| {
+            date.checked_sub_days(Days::new(days))
+                .unwrap_or(NaiveDate::MIN)
+        };
+
+        let (first, last) = if let Some(weeks) = heatmap.weeks {
+            (before(today, u64::from(weeks.max(1)) * 7 - 1), today)
+        } else {
+            let last = end.pred_opt().unwrap_or(start).max(start);
+            let window = HEATMAP_MAX_WEEKS * 7 - 1;
+
+            // show the year up to today for longer challenges
+            if (last - start).num_days().unsigned_abs() > window {
+                let last = today.clamp(
+                    start.checked_add_days(Days::new(window)).unwrap_or(last),
+                    last,
+                );
+                (before(last, window), last)
+            } else {
+                (start, last)
+            }
+        };
+
+        // weeks start on monday
+        let grid_start = before(first, u64::from(first.weekday().num_days_from_monday()));
+        let weeks = (last - grid_start).num_days().unsigned_abs() / 7 + 1;
+
+        let rows = (0..7)
+            .map(|weekday| {
+                (0..weeks)
+                    .map(|week| {
+                        let color = grid_start
+                            .checked_add_days(Days::new(week * 7 + weekday))
+                            .filter(|day| {
+                                (first..=last).contains(day) && (start..end).contains(day)
+                            })
+                            .map(|day| {
+                                if day > today {
+                                    &heatmap.colors.upcoming
+                                } else if checkin_days.contains(&day) {
+                                    &heatmap.colors.checkin
+                                } else {
+                                    &heatmap.colors.passed
+                                }
+                            });
+
+                        color.map_or_else(
+                            || " ".to_string(),
+                            |color| format!("\u{1b}[{color}m■\u{1b}[0m"),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        (usize::try_from(weeks).unwrap_or(usize::MAX), rows)
+    }
+
+    /// countdown to the next milestone, true if the milestone is reached today
+    fn milestone_text(&self, now: DateTime<FixedOffset>) -> Option<(String, bool)> {
+        let today = now.with_timezone(&Local).date_naive();
+        let end = self.end();
+
+        let (milestone, date) = self
+            .milestones
+            .iter()
+            .filter_map(|interval| {
+                (1..)
+                    .map_while(|n| {
+                        let milestone = interval.times(n)?;
+                        milestone
+                            .add_to(self.start)
+                            .filter(|date| *date <= end)
+                            .map(|date| (milestone, date.with_timezone(&Local).date_naive()))
+                    })
+                    .find(|(_, date)| *date >= today)
+            })
+            .min_by_key(|(_, date)| *date)?;
+
+        let days = (date - today).num_days();
+        if days == 0 {
+            Some((format!("Milestone reached: {}!", milestone.label()), true))
+        } else {
+            Some((
+                format!(
+                    "Next milestone: {} in {}",
+                    milestone.label(),
+                    plural(days, "Day")
+                ),
+                false,
+            ))
+        }
+    }
+
+    /// progress bar with the given width, colored with the terminal palette
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn progress_bar(
+        &self,
+        style: ProgressBar,
+        width: usize,
+        now: DateTime<FixedOffset>,
+    ) -> Option<String> {
+        let (full, empty) = match style {
+            ProgressBar::None => return None,
+            ProgressBar::Block => ("█", "░"),
+            ProgressBar::Line => ("━", "─"),
+        };
+
+        let (elapsed, total) = self.elapsed(now);
+        let progress = (elapsed as f64 / total as f64).clamp(0.0, 1.0);
+        let filled = ((progress * width as f64).round() as usize).min(width);
+
+        Some(format!(
+            "{}\u{1b}[90m{}\u{1b}[0m",
+            term_color(self.color(now), &full.repeat(filled), false),
+            empty.repeat(width - filled)
+        ))
+    }
+}
+
+/// centers the text within the width, using the width of the text in the terminal
+fn center(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    format!(
+        "{}{text}{}",
+        " ".repeat(padding / 2),
+        " ".repeat(padding - padding / 2)
+    )
+}
+
+/// stacked blocks for the challenges, all blocks share the same width
+pub fn challenges_module(
+    challenges: &[Challenge],
+    checkins: &[CheckIn],
+    icon: &str,
+    bar: ProgressBar,
+    heatmap: Option<&Heatmap>,
+    now: DateTime<FixedOffset>,
+) -> Vec<Value> {
+    let blocks: Vec<_> = challenges
+        .iter()
+        .map(|challenge| {
+            (
+                challenge,
+                challenge.title(icon, now),
+                challenge.text(now),
+                challenge.streak(checkins, now).map(|streak| streak.text()),
+                challenge.milestone_text(now),
+                heatmap.map(|heatmap| challenge.heatmap(checkins, heatmap, now)),
+            )
+        })
+        .collect();
+
+    let maxlen = blocks
+        .iter()
+        .map(|(_, title, body, streak, milestone, heatmap)| {
+            [
+                title.width(),
+                body.width(),
+                streak.as_ref().map_or(0, |text| text.width()),
+                milestone.as_ref().map_or(0, |(text, _)| text.width()),
+                heatmap.as_ref().map_or(0, |(weeks, _)| *weeks),
+            ]
+            .into_iter()
+            .max()
+            .unwrap_or_default()
+        })
+        .max()
+        .unwrap_or_default();
+
+    blocks
+        .into_iter()
+        .flat_map(|(challenge, title, body, streak, milestone, heatmap)| {
+            let title = json!({
+                "type": "custom",
+                "format": term_color(challenge.color(now), &center(&title, maxlen), true),
+            });
+            let sep = json!({
+                "type": "custom",
+                // fill line with box drawing dash
+                "format": term_color(challenge.color(now), &"─".repeat(maxlen), false),
+            });
+            let bar = challenge.progress_bar(bar, maxlen, now).map(|bar| {
+                json!({
+                    "type": "custom",
+                    "format": bar,
+                })
+            });
+            let body = json!({
+                "type": "custom",
+                "format": body,
+            });
+
+            let streak = streak.map(|text| {
+                json!({
+                    "type": "custom",
+                    "format": text,
+                })
+            });
+            // highlight the day a milestone is reached
+            let milestone = milestone.map(|(text, reached)| {
+                json!({
+                    "type": "custom",
+                    "format": if reached { term_color(2, &text, true) } else { text },
+                })
+            });
+
+            // the cells contain color codes, so center the heatmap using the number of weeks
+            let heatmap = heatmap.into_iter().flat_map(|(weeks, rows)| {
+                let padding = " ".repeat(maxlen.saturating_sub(weeks) / 2);
+                rows.into_iter().map(move |row| {
+                    json!({
+                        "type": "custom",
+                        "format": format!("{padding}{row}"),
+                    })
+                })
+            });
+
+            [json!("break"), title, sep]
+                .into_iter()
+                .chain(bar)
+                .chain([body])
+                .chain(streak)
+                .chain(milestone)
+                .chain(heatmap)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfc3339(date: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(date).expect("invalid test date")
+    }
+
+    fn local(date: &str) -> DateTime<FixedOffset> {
+        let naive =
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").expect("invalid test date");
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .expect("test date does not exist in the local timezone")
+            .fixed_offset()
+    }
+
+    #[test]
+    fn parse_date_timestamps() {
+        assert_eq!(parse_date("0"), Ok(rfc3339("1970-01-01T00:00:00Z")));
+        // past the 32 bit limit in 2038
+        assert_eq!(
+            parse_date("4102444800"),
+            Ok(rfc3339("2100-01-01T00:00:00Z"))
+        );
+        assert!(parse_date(&i64::MAX.to_string()).is_err());
+    }
+
+    #[test]
+    fn parse_date_with_timezone() {
+        let date = parse_date("2024-03-01T12:30:00+08:00");
+        assert_eq!(date, Ok(rfc3339("2024-03-01T04:30:00Z")));
+        assert_eq!(
+            date.map(|date| date.offset().local_minus_utc()),
+            Ok(8 * 3600)
+        );
+    }
+
+    #[test]
+    fn parse_date_without_timezone() {
+        assert_eq!(parse_date("2024-03-01"), Ok(local("2024-03-01 00:00:00")));
+        assert_eq!(parse_date(" 2024-03-01 "), Ok(local("2024-03-01 00:00:00")));
+        assert_eq!(
+            parse_date("2024-03-01T12:30"),
+            Ok(local("2024-03-01 12:30:00"))
+        );
+        assert_eq!(
+            parse_date("2024-03-01 12:30:45"),
+            Ok(local("2024-03-01 12:30:45"))
+        );
+    }
+
+    #[test]
+    fn parse_date_invalid() {
+        assert!(parse_date("").is_err());
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    fn from_args(args: &[&str]) -> Result<Challenge, String> {
+        use clap::Parser;
+
+        let args = WFetchArgs::try_parse_from(
+            ["wfetch", "--challenge", "--challenge-start", "2024-01-01"]
+                .iter()
+                .chain(args),
+        )
+        .map_err(|e| e.to_string())?;
+        Challenge::from_args(&args)
+    }
+
+    #[test]
+    fn from_args_length() {
+        let challenge = from_args(&[]);
+        assert_eq!(
+            challenge
+                .as_ref()
+                .map(|c| (c.years, c.months, c.duration, c.end)),
+            Ok((10, 0, None, None))
+        );
+
+        let challenge = from_args(&["--challenge-months", "6"]);
+        assert_eq!(challenge.as_ref().map(|c| (c.years, c.months)), Ok((0, 6)));
+
+        let challenge = from_args(&["--challenge-duration", "100d"]);
+        assert_eq!(
+            challenge.as_ref().map(|c| (c.years, c.months, c.duration)),
+            Ok((
+                0,
+                0,
+                Some(Duration {
+                    days: 100,
+                    ..Duration::default()
+                })
+            ))
+        );
+
+        let challenge = from_args(&["--challenge-end", "2030-01-01"]);
+        assert_eq!(
+            challenge.as_ref().map(|c| (c.years, c.months, c.end)),
+            Ok((0, 0, Some(local("2030-01-01 00:00:00"))))
+        );
+    }
+
+    #[test]
+    fn from_args_conflicting_length() {
+        assert!(from_args(&["--challenge-duration", "100d", "--challenge-years", "1"]).is_err());
+        assert!(from_args(&["--challenge-end", "2030-01-01", "--challenge-months", "1"]).is_err());
+        assert!(from_args(&["--challenge-end", "2023-01-01"]).is_err());
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(
+            parse_duration("100d"),
+            Ok(Duration {
+                days: 100,
+                ..Duration::default()
+            })
+        );
+        assert_eq!(
+            parse_duration("1y6m"),
+            Ok(Duration {
+                years: 1,
+                months: 6,
+                ..Duration::default()
+            })
+        );
+        assert_eq!(
+            parse_duration(" 2W3D "),
+            Ok(Duration {
+                weeks: 2,
+                days: 3,
+                ..Duration::default()
+            })
+        );
+        // repeated units are added together
+        assert_eq!(
+            parse_duration("1d1d"),
+            Ok(Duration {
+                days: 2,
+                ..Duration::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_duration_invalid() {
+        // trailing number without a unit
+        assert!(parse_duration("1y6").is_err());
+        assert!(parse_duration("100").is_err());
+        // empty durations
+        assert!(parse_duration("0d").is_err());
+        assert!(parse_duration("").is_err());
+        // unit without a number
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("1x").is_err());
+        // overflows
+        assert!(parse_duration("4294967296d").is_err());
+        assert!(parse_duration("4294967295d1d").is_err());
+    }
+}
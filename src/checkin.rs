@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeSet,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Days, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{WFetchResult, challenge::plural};
+
+/// a single line in the check-in log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckIn {
+    pub time: DateTime<FixedOffset>,
+    /// title or type of the challenge, a check-in without a challenge counts for all challenges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl CheckIn {
+    /// true if the check-in counts for the challenge with the given title or type
+    pub fn is_for(&self, names: &[&str]) -> bool {
+        self.challenge.as_ref().is_none_or(|challenge| {
+            names
+                .iter()
+                .any(|name| challenge.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+pub fn log_path() -> PathBuf {
+    dirs::data_dir()
+        .expect("could not get XDG_DATA_HOME")
+        .join("wfetch")
+        .join("checkins.jsonl")
+}
+
+/// appends a check-in at the given time to the log
+pub fn record(
+    time: DateTime<FixedOffset>,
+    challenge: Option<String>,
+    note: Option<String>,
+) -> WFetchResult<CheckIn> {
+    let checkin = CheckIn {
+        time,
+        challenge,
+        note,
+    };
+
+    let path = log_path();
+    std::fs::create_dir_all(path.parent().expect("could not get data dir"))?;
+
+    let mut log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(log, "{}", serde_json::to_string(&checkin)?)?;
+
+    Ok(checkin)
+}
+
+/// reads all check-ins from the log, a missing log has no check-ins
+pub fn read_log() -> WFetchResult<Vec<CheckIn>> {
+    let path = log_path();
+    let Ok(log) = std::fs::File::open(&path) else {
+        return Ok(Vec::new());
+    };
+
+    BufReader::new(log)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(i, line)| {
+            serde_json::from_str(&line?).map_err(|e| {
+                format!(
+                    "invalid check-in on line {} of {}\n{e}",
+                    i + 1,
+                    path.display()
+                )
+                .into()
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    /// consecutive days up to today, or yesterday if there is no check-in today yet
+    pub current: u64,
+    pub longest: u64,
+    pub days_since_last: i64,
+}
+
+impl Streak {
+    /// streaks from the days with a check-in, None if there are no check-ins
+    pub fn new(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> Option<Self> {
+        let last = *days.last()?;
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut prev: Option<NaiveDate> = None;
+        for day in days {
+            run = match prev {
+                Some(prev) if prev.checked_add_days(Days::new(1)) == Some(*day) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            prev = Some(*day);
+        }
+
+        let days_since_last = (today - last).num_days();
+        let current = if days_since_last <= 1 { run } else { 0 };
+
+        Some(Self {
+            current,
+            longest,
+            days_since_last,
+        })
+    }
+
+    pub fn text(&self) -> String {
+        let last = match self.days_since_last {
+            0 => "today".to_string(),
+            days => format!("{} ago", plural(days, "Day")),
+        };
+
+        format!(
+            "Streak: {} / Best: {} / Last: {last}",
+            plural(self.current.cast_signed(), "Day"),
+            plural(self.longest.cast_signed(), "Day"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").expect("invalid test date")
+    }
+
+    fn streak(days: &[&str], today: &str) -> Option<Streak> {
+        Streak::new(&days.iter().map(|day| date(day)).collect(), date(today))
+    }
+
+    #[test]
+    fn checkin_is_for_title_or_type() {
+        let checkin = |challenge: Option<&str>| CheckIn {
+            time: DateTime::default(),
+            challenge: challenge.map(String::from),
+            note: None,
+        };
+
+        assert!(checkin(None).is_for(&[]));
+        assert!(checkin(Some("gym")).is_for(&["Fitness", "gym"]));
+        assert!(checkin(Some("fitness")).is_for(&["Fitness", "gym"]));
+        assert!(!checkin(Some("reading")).is_for(&["Fitness", "gym"]));
+        assert!(!checkin(Some("gym")).is_for(&[]));
+    }
+
+    #[test]
+    fn streak_without_checkins() {
+        assert_eq!(streak(&[], "2024-03-10"), None);
+    }
+
+    #[test]
+    fn streak_checked_in_today() {
+        assert_eq!(
+            streak(&["2024-03-08", "2024-03-09", "2024-03-10"], "2024-03-10"),
+            Some(Streak {
+                current: 3,
+                longest: 3,
+                days_since_last: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn streak_checked_in_yesterday() {
+        // the streak continues until the end of today
+        assert_eq!(
+            streak(&["2024-03-08", "2024-03-09"], "2024-03-10"),
+            Some(Streak {
+                current: 2,
+                longest: 2,
+                days_since_last: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn streak_broken() {
+        assert_eq!(
+            streak(&["2024-03-07", "2024-03-08"], "2024-03-10"),
+            Some(Streak {
+                current: 0,
+                longest: 2,
+                days_since_last: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn streak_with_gaps() {
+        assert_eq!(
+            streak(
+                &[
+                    "2024-02-27",
+                    "2024-02-28",
+                    "2024-02-29",
+                    "2024-03-01",
+                    "2024-03-05",
+                    "2024-03-09",
+                    "2024-03-10",
+                ],
+                "2024-03-10"
+            ),
+            Some(Streak {
+                current: 2,
+                longest: 4,
+                days_since_last: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn streak_text() {
+        assert_eq!(
+            streak(&["2024-03-09", "2024-03-10"], "2024-03-10").map(|streak| streak.text()),
+            Some("Streak: 2 Days / Best: 2 Days / Last: today".to_string())
+        );
+        assert_eq!(
+            streak(&["2024-03-07"], "2024-03-10").map(|streak| streak.text()),
+            Some("Streak: 0 Days / Best: 1 Day / Last: 3 Days ago".to_string())
+        );
+    }
+}
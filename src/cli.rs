@@ -1,4 +1,4 @@
-use clap::{ArgGroup, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum, builder::FalseyValueParser};
 use clap_complete::{Shell, generate};
 
 #[allow(clippy::struct_excessive_bools)]
@@ -8,63 +8,42 @@ use clap_complete::{Shell, generate};
     about = "wfetch is an opinionated command-line fetch tool for displaying system information in a pretty way",
     version = env!("CARGO_PKG_VERSION"),
 )]
-// only one type of logo is allowed
-#[command(group = ArgGroup::new("logo")
-    .args(&[
-        #[cfg(feature = "nixos")]
-        "hollow",
-        #[cfg(feature = "nixos")]
-        "smooth",
-        #[cfg(feature = "nixos")]
-        "waifu",
-        #[cfg(feature = "nixos")]
-        "waifu2",
-        "wallpaper",
-        "wallpaper_ascii"
-    ])
-    .multiple(false))]
-#[command(group = ArgGroup::new("image_options").args(&[
-    "wallpaper",
-    #[cfg(feature = "nixos")]
-    "waifu",
-    #[cfg(feature = "nixos")]
-    "waifu2"
-]))]
 pub struct WFetchArgs {
-    #[cfg(feature = "nixos")]
-    #[arg(long, action, help = "Show hollow NixOS logo")]
-    pub hollow: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
 
-    #[cfg(feature = "nixos")]
-    #[arg(long, action, help = "Show NixOS logo with smooth edges")]
-    pub smooth: bool,
-
-    #[cfg(feature = "nixos")]
     #[arg(
         long,
+        env = "WFETCH_LOGO",
         action,
-        alias = "waifu1",
-        help = "Show waifu NixOS logo with dynamic colors"
+        value_name = "LOGO",
+        help = "Logo to display, see --list-logos",
+        long_help = "Logo to display, see --list-logos for the available logos\n(defaults to the NixOS logo on NixOS, otherwise the fastfetch logo for the distro)"
     )]
-    pub waifu: bool,
+    pub logo: Option<String>,
 
-    #[cfg(feature = "nixos")]
-    #[arg(long, action, help = "Show waifu NixOS logo 2 with dynamic colors")]
-    pub waifu2: bool,
+    #[arg(
+        long,
+        env = "WFETCH_LIST_LOGOS",
+        value_parser = FalseyValueParser::new(),
+        action,
+        help = "List the available logos"
+    )]
+    pub list_logos: bool,
 
     #[arg(
         long,
-        num_args = 0..=1,
-        value_name = "WALLPAPER",
-        default_missing_value = "",
+        env = "WFETCH_IMAGE",
         action,
-        help = "Show section of wallpaper",
-        long_help = "Show section of wallpaper, use \"-\" for stdin\n(supported backends: swww, swaybg, hyprpaper, gnome, cinnamon, mate)",
+        value_name = "IMAGE",
+        help = "Image for the wallpaper logos, use \"-\" for stdin",
+        long_help = "Image for the wallpaper logos, use \"-\" for stdin\n(defaults to the current wallpaper, supported backends: swww, swaybg, hyprpaper, feh, nitrogen, gnome, cinnamon, mate, budgie, xfce, lxqt)"
     )]
-    pub wallpaper: Option<String>,
+    pub image: Option<String>,
 
     #[arg(
         long,
+        env = "WFETCH_CROP",
         help = "Specify square area of the wallpaper to display in the format WxH+X+Y",
         value_name = "CROP_AREA"
     )]
@@ -72,83 +51,316 @@ pub struct WFetchArgs {
 
     #[arg(
         long,
-        num_args = 0..=1,
-        visible_aliases = ["ascii-wallpaper", "ascii"],
-        value_name = "WALLPAPER",
-        default_missing_value = "",
+        env = "WFETCH_CHALLENGE",
+        value_parser = FalseyValueParser::new(),
         action,
-        help = "Show section of wallpaper in ascii",
-        long_help = "Show section of wallpaper in ascii, use \"-\" for stdin\n(supported backends: swww, swaybg, hyprpaper, gnome, cinnamon, mate)",
+        help = "Show challenge progress"
     )]
-    pub wallpaper_ascii: Option<String>,
-
-    #[arg(long, action, help = "Show challenge progress")]
     pub challenge: bool,
 
     #[arg(
         long,
+        env = "WFETCH_CHALLENGE_START",
         action,
-        value_name = "TIMESTAMP",
+        alias = "challenge-timestamp",
+        value_name = "START",
         default_value = "1675821503",
-        help = "Start of the challenge as a UNIX timestamp in seconds"
+        help = "Start of the challenge",
+        long_help = "Start of the challenge as an ISO 8601 date or datetime, or a UNIX timestamp in seconds\n(dates and datetimes without a timezone are in local time)"
     )]
-    pub challenge_timestamp: i32,
+    pub challenge_start: String,
 
     #[arg(
         long,
+        env = "WFETCH_CHALLENGE_YEARS",
         action,
         value_name = "YEARS",
-        default_value = "10",
-        help = "Duration of challenge in years"
+        help = "Duration of challenge in years",
+        long_help = "Duration of challenge in years\n(defaults to 10 years without months, a duration or an end)"
     )]
-    pub challenge_years: u32,
+    pub challenge_years: Option<u32>,
 
     #[arg(
         long,
+        env = "WFETCH_CHALLENGE_MONTHS",
         action,
         value_name = "MONTHS",
-        default_value = "0",
         help = "Duration of challenge in months"
     )]
-    pub challenge_months: u32,
+    pub challenge_months: Option<u32>,
 
-    #[arg(long, action, help = "Type of the challenge, e.g. emacs")]
+    #[arg(
+        long,
+        env = "WFETCH_CHALLENGE_DURATION",
+        action,
+        value_name = "DURATION",
+        conflicts_with_all = ["challenge_years", "challenge_months"],
+        help = "Duration of the challenge, e.g. 100d, 12w or 1y6m",
+        long_help = "Duration of the challenge in years, months, weeks and days, e.g. 100d, 12w or 1y6m\n(cannot be used with --challenge-years or --challenge-months)"
+    )]
+    pub challenge_duration: Option<String>,
+
+    #[arg(
+        long,
+        env = "WFETCH_CHALLENGE_END",
+        action,
+        value_name = "END",
+        conflicts_with_all = ["challenge_years", "challenge_months", "challenge_duration"],
+        help = "End of the challenge",
+        long_help = "End of the challenge as an ISO 8601 date or datetime, or a UNIX timestamp in seconds\n(cannot be used with the duration of the challenge)"
+    )]
+    pub challenge_end: Option<String>,
+
+    #[arg(
+        long,
+        env = "WFETCH_CHALLENGE_MILESTONES",
+        value_delimiter = ',',
+        value_name = "DURATIONS",
+        help = "Comma separated milestones of the challenge, e.g. 100d or 1y",
+        long_help = "Comma separated milestones of the challenge that repeat from the start, e.g. 100d for every 100 days or 1y for each anniversary\n(shows a countdown to the next milestone)"
+    )]
+    pub challenge_milestones: Vec<String>,
+
+    #[arg(
+        long,
+        env = "WFETCH_CHALLENGE_TYPE",
+        action,
+        help = "Type of the challenge, e.g. emacs"
+    )]
     pub challenge_type: Option<String>,
 
-    #[arg(long, action, visible_aliases=["socket"], help = "Listen for SIGUSR2 to refresh output")]
+    #[arg(
+        long,
+        env = "WFETCH_CHALLENGE_BAR",
+        value_enum,
+        default_value_t = ProgressBar::None,
+        help = "Style of the challenge progress bar"
+    )]
+    pub challenge_bar: ProgressBar,
+
+    #[arg(
+        long,
+        env = "WFETCH_CHALLENGE_HEATMAP",
+        value_parser = FalseyValueParser::new(),
+        action,
+        help = "Show a heatmap of the days of the challenge, with the check-ins highlighted"
+    )]
+    pub challenge_heatmap: bool,
+
+    #[arg(
+        long,
+        env = "WFETCH_CHALLENGE_HEATMAP_WEEKS",
+        action,
+        value_name = "WEEKS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Number of weeks in the heatmap",
+        long_help = "Number of weeks in the heatmap, up to today\n(defaults to the whole challenge, or the last year of longer challenges)"
+    )]
+    pub challenge_heatmap_weeks: Option<u32>,
+
+    #[arg(
+        long,
+        env = "WFETCH_LISTEN",
+        value_parser = FalseyValueParser::new(),
+        action,
+        visible_aliases = ["socket"],
+        help = "Listen for SIGUSR2 to refresh output"
+    )]
     pub listen: bool,
 
-    #[arg(long, action, help = "Do not show colored keys")]
+    #[arg(
+        long,
+        env = "WFETCH_NO_COLOR_KEYS",
+        value_parser = FalseyValueParser::new(),
+        action,
+        help = "Do not show colored keys"
+    )]
     pub no_color_keys: bool,
 
     #[arg(
         long,
-        action,
-        help = "Image size in pixels",
-        requires = "image_options"
+        env = "WFETCH_KEY_COLORS",
+        value_delimiter = ',',
+        value_name = "COLORS",
+        value_parser = parse_key_color,
+        help = "Comma separated colors to cycle through for the keys",
+        long_help = "Comma separated colors to cycle through for the keys, as color names, ANSI codes or hex values\n(use \"terminal\" for the colors of the recolored logo from the terminal palette)"
+    )]
+    pub key_colors: Vec<String>,
+
+    #[arg(
+        long,
+        env = "WFETCH_ICONS",
+        value_enum,
+        default_value_t = Icons::Nerd,
+        help = "Icon set used for the keys"
     )]
+    pub icons: Icons,
+
+    #[arg(long, env = "WFETCH_IMAGE_SIZE", action, help = "Image size in pixels")]
     pub image_size: Option<u32>,
 
     #[arg(
         long,
+        env = "WFETCH_ASCII_SIZE",
         action,
         default_value = "70",
-        help = "Ascii size in characters",
-        requires = "wallpaper_ascii"
+        help = "Ascii size in characters"
     )]
     pub ascii_size: i32,
 
-    #[arg(long, action, help = "Scale factor for high DPI displays")]
+    #[arg(
+        long,
+        env = "WFETCH_SCALE",
+        action,
+        help = "Scale factor for high DPI displays"
+    )]
     pub scale: Option<f64>,
 
+    #[arg(
+        long,
+        env = "WFETCH_NOW",
+        action,
+        value_name = "DATETIME",
+        help = "Current time used for the challenge and countdowns",
+        long_help = "Current time used for the challenge and countdowns, as an ISO 8601 date or datetime, or a UNIX timestamp in seconds\n(for reproducible output, e.g. screenshots)"
+    )]
+    pub now: Option<String>,
+
+    #[arg(
+        long,
+        env = "WFETCH_UPTIME",
+        action,
+        value_name = "SECONDS",
+        help = "Uptime to show instead of the uptime of the system",
+        long_help = "Uptime in seconds to show instead of the uptime of the system\n(for reproducible output with --now)"
+    )]
+    pub uptime: Option<u64>,
+
+    #[arg(
+        long,
+        env = "WFETCH_PROFILE",
+        action,
+        value_name = "PROFILE",
+        help = "Use a named profile from the config file"
+    )]
+    pub profile: Option<String>,
+
     #[arg(
         long,
         value_enum,
         help = "Type of shell completion to generate",
-        hide = true,
-        exclusive = true
+        hide = true
     )]
     pub generate: Option<ShellCompletion>,
+
+    // deprecated logo flags from before --logo, mapped to --logo and --image
+    #[cfg(feature = "nixos")]
+    #[arg(long, action, hide = true, group = "deprecated_logo")]
+    pub hollow: bool,
+
+    #[cfg(feature = "nixos")]
+    #[arg(long, action, hide = true, group = "deprecated_logo")]
+    pub smooth: bool,
+
+    #[cfg(feature = "nixos")]
+    #[arg(long, action, alias = "waifu1", hide = true, group = "deprecated_logo")]
+    pub waifu: bool,
+
+    #[cfg(feature = "nixos")]
+    #[arg(long, action, hide = true, group = "deprecated_logo")]
+    pub waifu2: bool,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        value_name = "WALLPAPER",
+        default_missing_value = "",
+        action,
+        hide = true,
+        group = "deprecated_logo"
+    )]
+    pub wallpaper: Option<String>,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        aliases = ["ascii-wallpaper", "ascii"],
+        value_name = "WALLPAPER",
+        default_missing_value = "",
+        action,
+        hide = true,
+        group = "deprecated_logo"
+    )]
+    pub wallpaper_ascii: Option<String>,
+}
+
+/// validates a key color: a color name, ANSI code, hex value or "terminal"
+pub fn parse_key_color(color: &str) -> Result<String, String> {
+    let color = color.trim();
+
+    let valid = if let Some(hex) = color.strip_prefix('#') {
+        hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())
+    } else {
+        !color.is_empty()
+            && (color.chars().all(|c| c.is_ascii_alphabetic() || c == '_')
+                || color.chars().all(|c| c.is_ascii_digit() || c == ';'))
+    };
+
+    if valid {
+        Ok(color.to_string())
+    } else {
+        Err(format!(
+            "invalid color `{color}`, expected a color name, ANSI code or hex value"
+        ))
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Icons {
+    /// nerd font glyphs
+    #[default]
+    Nerd,
+    Emoji,
+    /// plain ascii, for a tty or terminals without a patched font
+    Ascii,
+    None,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Track the progress of challenges
+    Challenge {
+        #[command(subcommand)]
+        command: ChallengeCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ChallengeCommand {
+    /// Record a check-in for today
+    Checkin {
+        #[arg(
+            long,
+            action,
+            value_name = "CHALLENGE",
+            help = "Title or type of the challenge, checks in to all challenges by default"
+        )]
+        challenge: Option<String>,
+
+        #[arg(long, action, help = "Note for the check-in")]
+        note: Option<String>,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressBar {
+    #[default]
+    None,
+    /// full and shaded blocks
+    Block,
+    /// heavy and light box drawing lines
+    Line,
 }
 
 #[derive(Subcommand, ValueEnum, Debug, Clone)]
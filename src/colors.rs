@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::OnceLock};
 
 use image::Rgba;
 
@@ -154,6 +154,12 @@ pub fn most_contrasting_pair(colors: &[Rgba8]) -> (Rgba8, Rgba8) {
     most_contrasting_pair
 }
 
+/// the pair of terminal colors used to recolor the logos
+pub fn logo_colors(term_colors: &[Rgba8]) -> (Rgba8, Rgba8) {
+    // remove background color to get contrast
+    most_contrasting_pair(&term_colors[1..])
+}
+
 #[derive(serde::Deserialize)]
 struct NixInfo {
     colors: HashMap<String, String>,
@@ -179,7 +185,17 @@ fn term_colors_from_xterm() -> WFetchResult<Vec<Rgba8>> {
     (0..16).map(crate::xterm::query_term_color).collect()
 }
 
+/// colors of the terminal, only read once as querying the terminal is slow
 #[allow(clippy::module_name_repetitions)]
 pub fn get_term_colors() -> WFetchResult<Vec<Rgba8>> {
-    term_colors_from_json().or_else(|_| term_colors_from_xterm())
+    static TERM_COLORS: OnceLock<Result<Vec<Rgba8>, String>> = OnceLock::new();
+
+    TERM_COLORS
+        .get_or_init(|| {
+            term_colors_from_json()
+                .or_else(|_| term_colors_from_xterm())
+                .map_err(|e| e.to_string())
+        })
+        .clone()
+        .map_err(Into::into)
 }
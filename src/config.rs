@@ -0,0 +1,474 @@
+use std::{collections::HashMap, fmt, path::PathBuf};
+
+use clap::{ArgMatches, ValueEnum, parser::ValueSource};
+use serde::{
+    Deserialize, Deserializer,
+    de::{self, MapAccess, Visitor, value::MapAccessDeserializer},
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    WFetchResult,
+    challenge::{Challenge, DateValue, parse_date, parse_duration},
+    cli::{Icons, ProgressBar, WFetchArgs, parse_key_color},
+    countdown::Countdown,
+    icons::IconSets,
+};
+
+/// options read from the config file, keys are the same as the long cli flags
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub logo: Option<String>,
+    pub image: Option<String>,
+    pub crop: Option<String>,
+    pub challenge: Option<bool>,
+    #[serde(alias = "challenge-timestamp", deserialize_with = "deserialize_date")]
+    pub challenge_start: Option<String>,
+    pub challenge_years: Option<u32>,
+    pub challenge_months: Option<u32>,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub challenge_duration: Option<String>,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub challenge_end: Option<String>,
+    #[serde(deserialize_with = "deserialize_milestones")]
+    pub challenge_milestones: Option<Vec<String>>,
+    pub challenge_type: Option<String>,
+    #[serde(deserialize_with = "deserialize_value_enum")]
+    pub challenge_bar: Option<ProgressBar>,
+    pub challenge_heatmap: Option<bool>,
+    pub challenge_heatmap_weeks: Option<u32>,
+    /// challenges shown instead of the single challenge from the challenge options
+    pub challenges: Vec<Challenge>,
+    pub countdowns: Vec<Countdown>,
+    pub listen: Option<bool>,
+    pub no_color_keys: Option<bool>,
+    #[serde(deserialize_with = "deserialize_key_colors")]
+    pub key_colors: Option<Vec<String>>,
+    #[serde(deserialize_with = "deserialize_value_enum")]
+    pub icons: Option<Icons>,
+    pub image_size: Option<u32>,
+    pub ascii_size: Option<i32>,
+    pub scale: Option<f64>,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub now: Option<String>,
+    pub uptime: Option<u64>,
+    /// order of the modules, defaults to `DEFAULT_MODULES`
+    pub modules: Option<Vec<ModuleConfig>>,
+    /// extra icons and icon rules for each icon set, checked before the builtin icon rules
+    pub icon_sets: IconSets,
+    /// named profiles that are layered over the base config with --profile
+    pub profile: HashMap<String, Self>,
+}
+
+/// modules provided by wfetch that can be placed anywhere in the layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModuleKind {
+    Os,
+    Kernel,
+    Uptime,
+    Packages,
+    Cpu,
+    Gpu,
+    Memory,
+    Display,
+    Wm,
+    Terminal,
+    Shell,
+    Challenge,
+    Countdowns,
+    Colors,
+    Break,
+}
+
+pub const DEFAULT_MODULES: [ModuleKind; 17] = [
+    ModuleKind::Os,
+    ModuleKind::Kernel,
+    ModuleKind::Uptime,
+    ModuleKind::Packages,
+    ModuleKind::Break,
+    ModuleKind::Cpu,
+    ModuleKind::Gpu,
+    ModuleKind::Memory,
+    ModuleKind::Break,
+    ModuleKind::Display,
+    ModuleKind::Wm,
+    ModuleKind::Terminal,
+    ModuleKind::Shell,
+    ModuleKind::Challenge,
+    ModuleKind::Countdowns,
+    ModuleKind::Break,
+    ModuleKind::Colors,
+];
+
+/// a module in the layout, written as either a module name or a table of fastfetch module options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleConfig {
+    /// module provided by wfetch, the options override the generated module
+    Builtin(ModuleKind, Map<String, Value>),
+    /// any other fastfetch module, passed through to fastfetch
+    Raw(Map<String, Value>),
+}
+
+impl From<ModuleKind> for ModuleConfig {
+    fn from(kind: ModuleKind) -> Self {
+        Self::Builtin(kind, Map::new())
+    }
+}
+
+impl<'de> Deserialize<'de> for ModuleConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ModuleVisitor;
+
+        impl<'de> Visitor<'de> for ModuleVisitor {
+            type Value = ModuleConfig;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a module name or a module table")
+            }
+
+            fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                ModuleKind::deserialize(de::value::StrDeserializer::new(name))
+                    .map(Self::Value::from)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut options = Map::deserialize(MapAccessDeserializer::new(map))?;
+
+                let kind = match options.get("type") {
+                    Some(Value::String(kind)) => kind.clone(),
+                    Some(_) => return Err(de::Error::custom("module `type` must be a string")),
+                    None => return Err(de::Error::missing_field("type")),
+                };
+
+                // modules that are not provided by wfetch are passed through as is
+                match ModuleKind::deserialize(de::value::StrDeserializer::<A::Error>::new(&kind)) {
+                    Ok(kind) => {
+                        options.remove("type");
+                        Ok(ModuleConfig::Builtin(kind, options))
+                    }
+                    Err(_) => Ok(ModuleConfig::Raw(options)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(ModuleVisitor)
+    }
+}
+
+/// recursively merges the overlay table into the base table
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// parses a config value using the same names as the cli
+fn deserialize_value_enum<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    let value = String::deserialize(deserializer)?;
+    T::from_str(&value, true).map(Some).map_err(|_| {
+        let variants: Vec<_> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value().map(|v| format!("`{}`", v.get_name())))
+            .collect();
+        de::Error::custom(format!(
+            "unknown value `{value}`, expected one of {}",
+            variants.join(", ")
+        ))
+    })
+}
+
+/// a date as a string for the cli, validated in the same way
+fn deserialize_date<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let date = DateValue::deserialize(deserializer)?.to_string();
+    parse_date(&date).map_err(de::Error::custom)?;
+    Ok(Some(date))
+}
+
+fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let duration = String::deserialize(deserializer)?;
+    parse_duration(&duration).map_err(de::Error::custom)?;
+    Ok(Some(duration))
+}
+
+fn deserialize_milestones<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let milestones = Vec::<String>::deserialize(deserializer)?;
+    for milestone in &milestones {
+        parse_duration(milestone).map_err(de::Error::custom)?;
+    }
+    Ok(Some(milestones))
+}
+
+fn deserialize_key_colors<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|color| parse_key_color(color).map_err(de::Error::custom))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// true if the argument was explicitly passed on the command line or through a `WFETCH_*` variable
+fn is_explicit(matches: &ArgMatches, id: &str) -> bool {
+    matches!(
+        matches.value_source(id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+/// sets the value from the config, unless it was already passed on the command line or environment
+fn set<T>(matches: &ArgMatches, id: &str, dest: &mut T, value: Option<T>) {
+    if let Some(value) = value
+        && !is_explicit(matches, id)
+    {
+        *dest = value;
+    }
+}
+
+impl Config {
+    /// directory for the config file and user logos
+    pub fn dir() -> PathBuf {
+        dirs::config_dir()
+            .expect("could not get XDG_CONFIG_HOME")
+            .join("wfetch")
+    }
+
+    pub fn path() -> PathBuf {
+        Self::dir().join("config.toml")
+    }
+
+    /// reads the config file and applies the given profile, a missing config file is not an error
+    pub fn load(profile: Option<&str>) -> WFetchResult<Self> {
+        let path = Self::path();
+        if !path.exists() {
+            return match profile {
+                Some(name) => Err(format!("unknown profile `{name}`, no config file found").into()),
+                None => Ok(Self::default()),
+            };
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        let deserializer = toml::Deserializer::parse(&contents)
+            .map_err(|e| format!("could not parse {}\n{e}", path.display()))?;
+
+        // track the path to the failing key for a more helpful error
+        let config: Self = serde_path_to_error::deserialize(deserializer).map_err(|e| {
+            let key = e.path().to_string();
+            format!(
+                "invalid key `{key}` in {}\n{}",
+                path.display(),
+                e.into_inner()
+            )
+        })?;
+
+        if let Some(name) = config
+            .profile
+            .iter()
+            .find_map(|(name, profile)| (!profile.profile.is_empty()).then_some(name))
+        {
+            return Err(format!("profile `{name}` cannot contain nested profiles").into());
+        }
+
+        let mut config = match profile {
+            Some(name) => config.with_profile(&contents, name)?,
+            None => config,
+        };
+
+        config.challenges = std::mem::take(&mut config.challenges)
+            .into_iter()
+            .map(|challenge| {
+                challenge
+                    .validate()
+                    .map_err(|e| format!("invalid challenge in {}\n{e}", path.display()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(config)
+    }
+
+    /// layers the named profile over the base config
+    fn with_profile(&self, contents: &str, name: &str) -> WFetchResult<Self> {
+        if !self.profile.contains_key(name) {
+            let mut available: Vec<_> = self.profile.keys().map(String::as_str).collect();
+            available.sort_unstable();
+            return Err(format!(
+                "unknown profile `{name}`, available profiles: {}",
+                available.join(", ")
+            )
+            .into());
+        }
+
+        // the config has already been validated, so merge the raw tables to preserve unset keys
+        let mut base: toml::Table = toml::from_str(contents)?;
+        let mut profiles = base.remove("profile");
+        let overlay = profiles
+            .as_mut()
+            .and_then(|profiles| profiles.as_table_mut())
+            .and_then(|profiles| profiles.remove(name))
+            .and_then(|profile| profile.as_table().cloned())
+            .unwrap_or_default();
+
+        merge_tables(&mut base, overlay);
+        Ok(Self::deserialize(base)?)
+    }
+
+    /// layers the config under the environment variables and command line arguments
+    pub fn apply(&mut self, args: &mut WFetchArgs, matches: &ArgMatches) {
+        set(matches, "logo", &mut args.logo, self.logo.clone().map(Some));
+        set(
+            matches,
+            "image",
+            &mut args.image,
+            self.image.clone().map(Some),
+        );
+        set(matches, "crop", &mut args.crop, self.crop.clone().map(Some));
+        self.apply_challenge(args, matches);
+        set(matches, "listen", &mut args.listen, self.listen);
+        set(
+            matches,
+            "no_color_keys",
+            &mut args.no_color_keys,
+            self.no_color_keys,
+        );
+        set(
+            matches,
+            "image_size",
+            &mut args.image_size,
+            self.image_size.map(Some),
+        );
+        set(
+            matches,
+            "key_colors",
+            &mut args.key_colors,
+            self.key_colors.clone(),
+        );
+        set(matches, "icons", &mut args.icons, self.icons);
+        set(matches, "ascii_size", &mut args.ascii_size, self.ascii_size);
+        set(matches, "scale", &mut args.scale, self.scale.map(Some));
+        set(matches, "now", &mut args.now, self.now.clone().map(Some));
+        set(matches, "uptime", &mut args.uptime, self.uptime.map(Some));
+    }
+
+    /// layers the challenge options, same as `apply`
+    fn apply_challenge(&mut self, args: &mut WFetchArgs, matches: &ArgMatches) {
+        // challenge options from the command line replace the challenges from the config
+        if [
+            "challenge_start",
+            "challenge_years",
+            "challenge_months",
+            "challenge_duration",
+            "challenge_end",
+            "challenge_milestones",
+            "challenge_type",
+        ]
+        .iter()
+        .any(|id| is_explicit(matches, id))
+        {
+            self.challenges.clear();
+        }
+
+        set(matches, "challenge", &mut args.challenge, self.challenge);
+        set(
+            matches,
+            "challenge_start",
+            &mut args.challenge_start,
+            self.challenge_start.clone(),
+        );
+        // a length from the command line replaces the whole length from the config, as
+        // years and months cannot be combined with a duration or end
+        let length = [
+            "challenge_years",
+            "challenge_months",
+            "challenge_duration",
+            "challenge_end",
+        ];
+        if !length.iter().any(|id| is_explicit(matches, id)) {
+            set(
+                matches,
+                "challenge_years",
+                &mut args.challenge_years,
+                self.challenge_years.map(Some),
+            );
+            set(
+                matches,
+                "challenge_months",
+                &mut args.challenge_months,
+                self.challenge_months.map(Some),
+            );
+            set(
+                matches,
+                "challenge_duration",
+                &mut args.challenge_duration,
+                self.challenge_duration.clone().map(Some),
+            );
+            set(
+                matches,
+                "challenge_end",
+                &mut args.challenge_end,
+                self.challenge_end.clone().map(Some),
+            );
+        }
+        set(
+            matches,
+            "challenge_milestones",
+            &mut args.challenge_milestones,
+            self.challenge_milestones.clone(),
+        );
+        set(
+            matches,
+            "challenge_type",
+            &mut args.challenge_type,
+            self.challenge_type.clone().map(Some),
+        );
+        set(
+            matches,
+            "challenge_bar",
+            &mut args.challenge_bar,
+            self.challenge_bar,
+        );
+        set(
+            matches,
+            "challenge_heatmap",
+            &mut args.challenge_heatmap,
+            self.challenge_heatmap,
+        );
+        set(
+            matches,
+            "challenge_heatmap_weeks",
+            &mut args.challenge_heatmap_weeks,
+            self.challenge_heatmap_weeks.map(Some),
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, FixedOffset, Local};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::challenge::{days_until, deserialize_date, plural};
+
+/// a countdown to a date, or a count up from a date that has passed, from the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Countdown {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub date: DateTime<FixedOffset>,
+    /// replaces the default text, with `{name}` and `{days}` as placeholders
+    pub format: Option<String>,
+}
+
+impl Countdown {
+    /// e.g. "Release freeze in 12 Days" or "Last incident 45 Days ago"
+    // placeholders are replaced by hand, as the format comes from the config
+    #[allow(clippy::literal_string_with_formatting_args)]
+    pub fn text(&self, now: DateTime<FixedOffset>) -> String {
+        let days = days_until(self.date, now.with_timezone(&Local).date_naive());
+
+        let format = self.format.as_deref().unwrap_or(match days {
+            0 => "{name} today",
+            1.. => "{name} in {days}",
+            _ => "{name} {days} ago",
+        });
+
+        format
+            .replace("{name}", &self.name)
+            .replace("{days}", &plural(days.abs(), "Day"))
+    }
+}
+
+/// a line for each countdown
+pub fn countdowns_module(countdowns: &[Countdown], now: DateTime<FixedOffset>) -> Vec<Value> {
+    countdowns
+        .iter()
+        .map(|countdown| {
+            json!({
+                "type": "custom",
+                "format": countdown.text(now),
+            })
+        })
+        .collect()
+}
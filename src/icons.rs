@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use crate::cli::Icons;
+
+fn deserialize_regex<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|re| Regex::new(&re).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IconRule {
+    pub contains: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub regex: Option<Regex>,
+    pub icon: String,
+}
+
+impl IconRule {
+    /// value is expected to be lowercased
+    pub fn matches(&self, value: &str) -> bool {
+        if value.is_empty() {
+            return false;
+        }
+
+        self.contains.as_ref().is_some_and(|s| value.contains(s))
+            || self.regex.as_ref().is_some_and(|re| re.is_match(value))
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IconTable {
+    /// icon used when no rules match
+    pub default: Option<String>,
+    pub rules: Vec<IconRule>,
+}
+
+impl IconTable {
+    /// returns the icon of the first matching rule, or the default icon
+    pub fn icon(&self, value: &str) -> String {
+        let value = value.to_lowercase();
+
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(&value))
+            .map(|rule| rule.icon.clone())
+            .or_else(|| self.default.clone())
+            .unwrap_or_default()
+    }
+
+    /// layers the other table over this one, rules from the other table are checked first
+    fn extend(&mut self, other: &Self) {
+        if other.default.is_some() {
+            self.default.clone_from(&other.default);
+        }
+
+        self.rules.splice(0..0, other.rules.iter().cloned());
+    }
+}
+
+/// icons for the keys of the modules
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IconSet {
+    /// icons for modules that do not depend on the system, by fastfetch module type
+    pub keys: HashMap<String, String>,
+    pub os: IconTable,
+    /// matched against both the desktop environment and the window manager
+    pub wm: IconTable,
+    pub terminal: IconTable,
+    pub shell: IconTable,
+    /// desktops shown with the de module, from `IconSets`
+    #[serde(skip)]
+    pub desktops: Vec<String>,
+}
+
+impl IconSet {
+    /// returns the icon for the module type, or the default icon
+    pub fn key(&self, kind: &str) -> String {
+        self.keys
+            .get(kind)
+            .or_else(|| self.keys.get("default"))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// layers the other set over this one
+    fn extend(&mut self, other: &Self) {
+        self.keys.extend(other.keys.clone());
+        self.os.extend(&other.os);
+        self.wm.extend(&other.wm);
+        self.terminal.extend(&other.terminal);
+        self.shell.extend(&other.shell);
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IconSets {
+    /// desktops shown with the de module instead of the wm module, for every icon set
+    pub desktops: Vec<String>,
+    pub nerd: IconSet,
+    pub emoji: IconSet,
+    pub ascii: IconSet,
+}
+
+impl IconSets {
+    /// icons that ship with wfetch
+    pub fn builtin() -> Self {
+        toml::from_str(include_str!("../assets/icons.toml")).expect("invalid builtin icons.toml")
+    }
+
+    /// the builtin icon set for the style, extended or overridden by the icon sets from the config
+    pub fn resolve(style: Icons, overrides: &Self) -> IconSet {
+        let builtin = Self::builtin();
+        let desktops = builtin
+            .desktops
+            .into_iter()
+            .chain(overrides.desktops.iter().cloned())
+            .collect();
+
+        let (mut set, overrides) = match style {
+            Icons::Nerd => (builtin.nerd, &overrides.nerd),
+            Icons::Emoji => (builtin.emoji, &overrides.emoji),
+            Icons::Ascii => (builtin.ascii, &overrides.ascii),
+            Icons::None => (IconSet::default(), &IconSet::default()),
+        };
+
+        set.extend(overrides);
+        set.desktops = desktops;
+        set
+    }
+}
+
+/// formats the key for a module, dropping the icon if there is none
+pub fn key(icon: &str, label: &str) -> String {
+    if icon.is_empty() {
+        label.to_string()
+    } else {
+        format!("{icon} {label}")
+    }
+}
+
+/// width of the keys: icon + space + 3 letters + separator
+pub const fn key_width(style: Icons) -> u32 {
+    let icon = match style {
+        Icons::Nerd | Icons::Ascii => 1 + 1,
+        // emoji are double width
+        Icons::Emoji => 2 + 1,
+        Icons::None => 0,
+    };
+
+    icon + 3 + 3
+}
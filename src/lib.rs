@@ -1,8 +1,15 @@
-use crate::cli::WFetchArgs;
-use chrono::{DateTime, Datelike, NaiveDate, Timelike};
+use crate::{
+    challenge::{Challenge, Heatmap, HeatmapColors, challenges_module, plural},
+    cli::WFetchArgs,
+    colors::{Rgba8, Rgba8Ext, get_term_colors},
+    config::{Config, DEFAULT_MODULES, ModuleConfig, ModuleKind},
+    countdown::countdowns_module,
+    icons::{IconSet, IconSets, key, key_width},
+};
+use chrono::{DateTime, FixedOffset};
 use logos::Logo;
 use nix::unistd::getpgrp;
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 use std::{
     collections::HashMap,
     env,
@@ -11,9 +18,15 @@ use std::{
     process::{Command, Stdio},
 };
 
+pub mod challenge;
+pub mod checkin;
 pub mod cli;
 pub mod colors;
+pub mod config;
+pub mod countdown;
+pub mod icons;
 pub mod logos;
+pub mod output;
 pub mod wallpaper;
 pub mod xterm;
 
@@ -80,23 +93,16 @@ fn term_color(color: i32, text: &str, bold: bool) -> String {
     format!("\u{1b}[{bold_format}{}m{text}\u{1b}[0m", 30 + color)
 }
 
-fn last_day_of_month(year: i32, month: u32) -> u32 {
-    let month = if month == 12 { 1 } else { month };
-    let year = if month == 12 { year + 1 } else { year };
-
-    let first_day_of_next_month =
-        NaiveDate::from_ymd_opt(year, month + 1, 1).expect("cannot create ymd");
-    (first_day_of_next_month - chrono::Duration::try_days(1).expect("cannot create duration")).day()
-}
-
 #[derive(Debug)]
 pub struct Fastfetch {
     args: WFetchArgs,
+    config: Config,
+    icons: IconSet,
     preprocess: HashMap<String, String>,
 }
 
 impl Fastfetch {
-    pub fn new(args: &WFetchArgs) -> Self {
+    pub fn new(args: &WFetchArgs, config: &Config) -> Self {
         /*
         run fastfetch in the same process group as the terminal using the
         setsid syscall in order for fastfetch to properly detect the
@@ -121,6 +127,8 @@ impl Fastfetch {
         Self {
             preprocess,
             args: args.clone(),
+            config: config.clone(),
+            icons: IconSets::resolve(args.icons, &config.icon_sets),
         }
     }
 
@@ -130,74 +138,11 @@ impl Fastfetch {
     }
 
     fn os_module(&self) -> serde_json::Value {
-        let os = self.preprocess("OS").to_lowercase();
-
-        for (s, key) in &[
-            ("nixos", ""),
-            ("kali", ""),
-            ("rocky", ""),
-            ("mint", "󰣭"),
-            ("alpine", ""),
-            ("archcraft", ""),
-            ("archlabs", ""),
-            ("arcolinux", ""),
-            ("artix", ""),
-            ("centos", ""),
-            ("coreos", ""),
-            ("crystal", ""),
-            ("debian", ""),
-            ("deepin", ""),
-            ("devuan", ""),
-            ("elementary", ""),
-            ("endeavour", ""),
-            ("fedora", ""),
-            ("macos", ""),
-            ("freebsd", ""),
-            ("garuda", ""),
-            ("gentoo", ""),
-            ("hyperbola", ""),
-            ("illumos", ""),
-            ("kubuntu", ""),
-            ("locos", ""),
-            ("mageia", ""),
-            ("mandriva", ""),
-            ("manjaro", ""),
-            ("mxlinux", ""),
-            ("openbsd", ""),
-            ("opensuse", ""),
-            ("parabola", ""),
-            ("parrot", ""),
-            ("puppy", ""),
-            ("qubes", ""),
-            ("redhat", ""),
-            ("sabayon", ""),
-            ("slackware", ""),
-            ("solus", ""),
-            ("tails", ""),
-            ("trisquel", ""),
-            ("ubuntu", ""),
-            ("vanilla", ""),
-            ("void", ""),
-            ("xerolinux", ""),
-            ("xorin", ""),
-            ("guix", ""),
-            ("pop!_os", ""),
-            ("rhel", ""),
-            ("arch", ""),
-            ("alma", ""),
-        ] {
-            if os.contains(s) {
-                return json!({
-                    "type": "os",
-                    "key": format!("{key} OS"),
-                    "format": "{3}"
-                });
-            }
-        }
+        let icon = self.icons.os.icon(&self.preprocess("OS"));
 
         json!({
             "type": "os",
-            "key": format!(" OS"),
+            "key": key(&icon, "OS"),
             "format": "{3}"
         })
     }
@@ -206,67 +151,34 @@ impl Fastfetch {
         let de = self.preprocess("DE").to_lowercase();
         let wm = self.preprocess("WM").to_lowercase();
 
-        for (s, key) in &[
-            ("hyprland", ""),
-            ("awesome", ""),
-            ("bspwm", ""),
-            ("budgie", ""),
-            ("cinnamon", ""),
-            ("dwm", ""),
-            ("enlightenment", ""),
-            ("fluxbox", ""),
-            ("gnome", ""),
-            ("i3", ""),
-            ("lxde", ""),
-            ("lxqt", ""),
-            ("mate", ""),
-            ("plasma", ""),
-            ("qtile", ""),
-            ("sway", ""),
-            ("xfce", ""),
-            ("xmonad", ""),
-        ] {
-            if de.contains(s) {
-                return json!({ "type": "de", "key": format!("{key} DE"), "format": "{2} ({3})" });
+        // the module does not depend on the icon set, only the icon is looked up
+        for name in &self.icons.desktops {
+            if de.contains(name.as_str()) {
+                let icon = self.icons.wm.icon(&de);
+                return json!({ "type": "de", "key": key(&icon, "DE"), "format": "{2} ({3})" });
             }
-            if wm.contains(s) {
-                return json!({ "type": "wm", "key": format!("{key} WM"), "format": "{2}" });
+            if wm.contains(name.as_str()) {
+                break;
             }
         }
 
-        json!({ "type": "wm", "key": format!("󰕮 WM"), "format": "{2}" })
+        let icon = self.icons.wm.icon(&wm);
+        json!({ "type": "wm", "key": key(&icon, "WM"), "format": "{2}" })
     }
 
-    #[allow(clippy::unused_self)]
     fn shell_module(&self) -> serde_json::Value {
-        if let Ok(starship_shell) = std::env::var("STARSHIP_SHELL") {
-            if starship_shell.ends_with("fish") {
-                return json!({
-                    "type": "command",
-                    "key": "󰈺 SH",
-                    "text": "echo fish",
-                });
-            }
-            return json!({
-                "type": "command",
-                "key": " SH",
-                "text": format!("echo {starship_shell}"),
-            });
-        }
-
-        let shell = std::env::var("SHELL").unwrap_or_default();
+        let shell = std::env::var("STARSHIP_SHELL")
+            .or_else(|_| std::env::var("SHELL"))
+            .unwrap_or_default();
         let shell = shell
             .rsplit_once('/')
             .map_or_else(|| shell.clone(), |(_, end)| end.to_string());
 
-        let icon = match shell.as_str() {
-            "fish" => "󰈺",
-            _ => "",
-        };
+        let icon = self.icons.shell.icon(&shell);
 
         json!({
             "type": "command",
-            "key": format!("{icon} SH"),
+            "key": key(&icon, "SH"),
             "text": format!("echo {shell}"),
         })
     }
@@ -283,24 +195,21 @@ impl Fastfetch {
 
     fn terminal_module(&self) -> serde_json::Value {
         let term = self.preprocess("Terminal");
-        let icon = match term.as_str() {
-            "kitty" => "󰄛",
-            "ghostty" => "󰊠",
-            _ => "",
-        };
+        let icon = self.icons.terminal.icon(&term);
 
         json!({
            "type": "command",
-           "key": format!("{icon} TER"),
+           "key": key(&icon, "TER"),
            "text": format!("echo {term}")
         })
     }
 
     fn gpu_module(&self) -> Vec<serde_json::Value> {
+        let gpu_key = key(&self.icons.key("gpu"), "GPU");
         let gpu_json = |gpu: &str| {
             json!({
                 "type": "command",
-                "key": " GPU",
+                "key": gpu_key,
                 "text": format!("echo {gpu}"),
             })
         };
@@ -338,149 +247,275 @@ impl Fastfetch {
             .collect()
     }
 
-    #[allow(clippy::cast_precision_loss, clippy::cast_possible_wrap)]
-    fn challenge_text(&self) -> String {
-        let start = DateTime::parse_from_str(&self.args.challenge_timestamp.to_string(), "%s")
-            .expect("could not parse start timestamp");
+    /// challenges from the config, or a single challenge from the command line
+    fn challenges(&self) -> Vec<Challenge> {
+        if self.config.challenges.is_empty() {
+            match Challenge::from_args(&self.args) {
+                Ok(challenge) => vec![challenge],
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        } else {
+            self.config.challenges.clone()
+        }
+    }
 
-        let mths = self.args.challenge_months % 12;
-        let yrs = self.args.challenge_years + self.args.challenge_months / 12;
+    fn challenge_module(&self) -> Vec<serde_json::Value> {
+        let checkins = checkin::read_log().unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        });
 
-        let final_mth = if start.month() + mths > 12 {
-            start.month() + mths - 12
-        } else {
-            start.month() + mths
-        };
-        let final_yr = if start.month() + mths > 12 {
-            start.year() + yrs as i32 + 1
-        } else {
-            start.year() + yrs as i32
-        };
-        let final_day = std::cmp::min(start.day(), last_day_of_month(final_yr, final_mth));
+        challenges_module(
+            &self.challenges(),
+            &checkins,
+            // only an explicit challenge icon, not the default icon of the set
+            &self
+                .icons
+                .keys
+                .get("challenge")
+                .cloned()
+                .unwrap_or_default(),
+            self.args.challenge_bar,
+            self.heatmap().as_ref(),
+            self.now(),
+        )
+    }
 
-        let end = NaiveDate::from_ymd_opt(final_yr, final_mth, final_day)
-            .expect("invalid end date")
-            .and_time(
-                chrono::NaiveTime::from_hms_opt(start.hour(), start.minute(), start.second())
-                    .expect("invalid end time"),
-            );
+    /// the current time, or the time from --now
+    fn now(&self) -> DateTime<FixedOffset> {
+        challenge::now(&self.args).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        })
+    }
 
-        let now = chrono::offset::Local::now();
+    /// a fixed uptime from --uptime, as fastfetch always shows the uptime of the system
+    fn uptime_module(&self) -> Value {
+        let key = self.key("uptime", "UP");
+        let Some(uptime) = self.args.uptime else {
+            return json!({ "type": "uptime", "key": key });
+        };
+        let uptime = i64::try_from(uptime).unwrap_or(i64::MAX);
+
+        let text = [
+            (uptime / 86400, "day"),
+            (uptime / 3600 % 24, "hour"),
+            (uptime / 60 % 60, "min"),
+        ]
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| plural(*value, unit))
+        .collect::<Vec<_>>()
+        .join(", ");
 
-        let elapsed = now.timestamp() - start.timestamp();
-        let total = end.and_utc().timestamp() - start.timestamp();
+        json!({
+            "type": "custom",
+            "key": key,
+            "format": if text.is_empty() { plural(0, "min") } else { text },
+        })
+    }
 
-        let percent = elapsed as f32 / total as f32 * 100.0;
+    fn heatmap(&self) -> Option<Heatmap> {
+        self.args.challenge_heatmap.then(|| Heatmap {
+            weeks: self.args.challenge_heatmap_weeks,
+            colors: Self::heatmap_colors(),
+        })
+    }
 
-        let elapsed_days = elapsed / 60 / 60 / 24;
-        let total_days = total / 60 / 60 / 24;
+    /// check-ins in the accent color of the terminal palette, with a dimmed accent for the other days
+    fn heatmap_colors() -> HeatmapColors {
+        // querying the terminal breaks tmux, same as the logo
+        if env::var("TMUX").is_ok() {
+            return HeatmapColors::default();
+        }
 
-        format!("{elapsed_days} Days / {total_days} Days ({percent:.2}%)")
+        get_term_colors().map_or_else(
+            |_| HeatmapColors::default(),
+            |term_colors| {
+                let (accent, _) = colors::logo_colors(&term_colors);
+                HeatmapColors {
+                    checkin: accent.term_fg(),
+                    passed: accent.multiply(Rgba8::from([96, 96, 96, 255])).term_fg(),
+                    upcoming: term_colors[8].term_fg(),
+                }
+            },
+        )
     }
 
-    fn challenge_title(&self) -> String {
-        let mut segments: Vec<String> = Vec::new();
-        segments.push(if self.args.challenge_years == 0 {
-            String::new()
-        } else {
-            format!("{} YEAR", self.args.challenge_years)
-        });
+    /// fastfetch module that is not provided by wfetch, with a key in the same style as the other modules
+    fn raw_module(&self, options: &Map<String, Value>) -> Value {
+        let kind = options
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        let label = match kind {
+            "battery" => "BAT",
+            "bios" => "BIO",
+            "bluetooth" => "BT",
+            "board" => "MB",
+            "camera" => "CAM",
+            "datetime" => "NOW",
+            "disk" => "DSK",
+            "editor" => "EDT",
+            "font" => "FNT",
+            "gamepad" => "PAD",
+            "host" => "HST",
+            "loadavg" => "LD",
+            "locale" => "LOC",
+            "localip" => "IP",
+            "media" | "player" => "MUS",
+            "monitor" => "MON",
+            "physicalmemory" => "MEM",
+            "processes" => "PRC",
+            "publicip" => "WAN",
+            "sound" => "VOL",
+            "swap" => "SWP",
+            "theme" => "THM",
+            "users" => "USR",
+            "weather" => "WTH",
+            "wifi" => "NET",
+            _ => "",
+        };
 
-        segments.push(if self.args.challenge_months == 0 {
-            String::new()
+        let label = if label.is_empty() {
+            kind.chars().take(3).collect::<String>().to_uppercase()
         } else {
-            format!("{} MONTH", self.args.challenge_months)
-        });
+            label.to_string()
+        };
 
-        segments.push(match &self.args.challenge_type {
-            None => String::new(),
-            Some(t) => t.to_owned().to_uppercase(),
-        });
+        json!({ "type": kind, "key": self.key(kind, &label) })
+    }
 
-        let title = segments
-            .into_iter()
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join(" ");
+    /// key for a module with an icon from the icon set
+    fn key(&self, kind: &str, label: &str) -> String {
+        key(&self.icons.key(kind), label)
+    }
 
-        format!("  {title} CHALLENGE  ")
+    /// returns the json for a module, some modules can produce multiple lines
+    fn module(&self, kind: ModuleKind) -> Vec<Value> {
+        match kind {
+            ModuleKind::Os => vec![self.os_module()],
+            ModuleKind::Kernel => {
+                vec![json!({ "type": "kernel", "key": self.key("kernel", "VER"), })]
+            }
+            ModuleKind::Uptime => vec![self.uptime_module()],
+            ModuleKind::Packages => {
+                vec![json!({ "type": "packages", "key": self.key("packages", "PKG"), })]
+            }
+            ModuleKind::Cpu => {
+                vec![
+                    json!({ "type": "cpu", "key": self.key("cpu", "CPU"), "format": "{1} ({5})", }),
+                ]
+            }
+            // might have multiple gpus
+            ModuleKind::Gpu => self.gpu_module(),
+            ModuleKind::Memory => vec![
+                json!({ "type": "memory", "key": self.key("memory", "RAM"), "format": "{/1}{-}{/}{/2}{-}{/}{} / {}" }),
+            ],
+            ModuleKind::Display => {
+                vec![
+                    json!({ "type": "display", "key": self.key("display", "RES"), "compactType": "scaled" }),
+                ]
+            }
+            ModuleKind::Wm => vec![self.wm_module()],
+            ModuleKind::Terminal => vec![self.terminal_module()],
+            ModuleKind::Shell => vec![self.shell_module()],
+            // optional challenge block
+            ModuleKind::Challenge if self.args.challenge => self.challenge_module(),
+            ModuleKind::Challenge => Vec::new(),
+            ModuleKind::Countdowns => countdowns_module(&self.config.countdowns, self.now()),
+            ModuleKind::Colors => vec![json!({ "type": "colors", "symbol": "circle", })],
+            ModuleKind::Break => vec![json!("break")],
+        }
     }
 
-    fn challenge_module(&self) -> Vec<serde_json::Value> {
-        let title = self.challenge_title();
-        let body = self.challenge_text();
-        let maxlen = std::cmp::max(title.len(), body.len());
+    /// colors to cycle through for the keys, in a format suitable for fastfetch
+    fn key_colors(&self) -> Vec<String> {
+        let default_colors = || {
+            ["green", "yellow", "blue", "magenta", "cyan"]
+                .map(String::from)
+                .to_vec()
+        };
 
-        let title = json!({
-            "type": "custom",
-            "format": term_color(3, &format!("{title:^maxlen$}"), true),
-        });
-        let sep = json!({
-            "type": "custom",
-            // fill line with box drawing dash
-            "format": term_color(3, &format!("{:─^maxlen$}", ""), false),
-        });
-        let body = json!({
-            "type": "custom",
-            "format": body,
-        });
+        if self.args.key_colors.is_empty() {
+            return default_colors();
+        }
+
+        if self.args.key_colors.iter().any(|color| color == "terminal") {
+            // querying the terminal breaks tmux, same as the logo
+            if env::var("TMUX").is_ok() {
+                return default_colors();
+            }
+
+            return get_term_colors().map_or_else(
+                |_| default_colors(),
+                |term_colors| {
+                    let (color1, color2) = colors::logo_colors(&term_colors);
+                    vec![color1.term_fg(), color2.term_fg()]
+                },
+            );
+        }
 
-        vec![json!("break"), title, sep, body]
+        self.args
+            .key_colors
+            .iter()
+            .map(|color| {
+                color
+                    .strip_prefix('#')
+                    .and_then(|_| Rgba8::from_str(color).ok())
+                    .map_or_else(|| color.clone(), |color| color.term_fg())
+            })
+            .collect()
     }
 
     pub fn create_config(&self, config_jsonc: &PathBuf) {
-        let kernel = json!({ "type": "kernel", "key": " VER", });
-        let uptime = json!({ "type": "uptime", "key": "󰅐 UP", });
-        let packages = json!({ "type": "packages", "key": "󰏖 PKG", });
-        let display = json!({ "type": "display", "key": "󰍹 RES", "compactType": "scaled" });
-        let cpu = json!({ "type": "cpu", "key": " CPU", "format": "{1} ({5})", });
-        let memory =
-            json!({ "type": "memory", "key": " RAM", "format": "{/1}{-}{/}{/2}{-}{/}{} / {}" });
-        let color = json!({ "type": "colors", "symbol": "circle", });
-
-        let mut modules = vec![
-            self.os_module(),
-            kernel,
-            uptime,
-            packages,
-            json!("break"),
-            cpu,
-        ];
-        // might have multiple gpus
-        modules.extend(self.gpu_module());
-        modules.extend([
-            memory,
-            json!("break"),
-            display,
-            self.wm_module(),
-            self.terminal_module(),
-            self.shell_module(),
-        ]);
-
-        // set colors for modules
-        if !self.args.no_color_keys {
-            let colors = ["green", "yellow", "blue", "magenta", "cyan"];
-            for (i, module) in modules.iter_mut().enumerate() {
-                if let Value::Object(module) = module {
-                    module.insert("keyColor".into(), json!(colors[i % colors.len()]));
+        let layout = self
+            .config
+            .modules
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MODULES.map(ModuleConfig::from).to_vec());
+
+        let colors = self.key_colors();
+        let mut modules = Vec::new();
+        for entry in layout {
+            let (kind, generated, options) = match entry {
+                ModuleConfig::Builtin(kind, options) => (Some(kind), self.module(kind), options),
+                ModuleConfig::Raw(options) => (None, vec![self.raw_module(&options)], options),
+            };
+
+            for mut module in generated {
+                if let Value::Object(module) = &mut module {
+                    // set colors for modules, the challenge, countdowns and color blocks have no keys
+                    if !self.args.no_color_keys
+                        && !matches!(
+                            kind,
+                            Some(
+                                ModuleKind::Challenge | ModuleKind::Countdowns | ModuleKind::Colors
+                            )
+                        )
+                    {
+                        module.insert(
+                            "keyColor".into(),
+                            json!(colors[modules.len() % colors.len()]),
+                        );
+                    }
+
+                    module.extend(options.clone());
                 }
+                modules.push(module);
             }
         }
 
-        // optional challenge block
-        if self.args.challenge {
-            modules.extend_from_slice(&self.challenge_module());
-        }
-
-        modules.extend([json!("break"), color]);
-
         let contents = json!( {
             "$schema": "https://github.com/fastfetch-cli/fastfetch/raw/dev/doc/json_schema.json",
             "display": {
                 "separator": "   ",
-                // icon + space + 3 letters + separator
                 "key": {
-                    "width": 1 + 1 + 3 + 3,
+                    "width": key_width(self.args.icons),
                 },
                 "size": {
                     "binaryPrefix": "si",
@@ -1,7 +1,8 @@
 use std::{
     io::Read,
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::OnceLock,
 };
 
 use fast_image_resize::images::Image;
@@ -10,66 +11,37 @@ use image::{ImageBuffer, ImageEncoder, ImageReader, Rgba, codecs::png::PngEncode
 use serde::Deserialize;
 use serde_json::{Value as JsonValue, json};
 
+#[cfg(feature = "nixos")]
+use crate::asset_path;
 use crate::{
-    asset_path,
+    WFetchResult,
     cli::WFetchArgs,
     colors::{self, Rgba8, Rgba8Ext},
-    create_output_file,
+    config::Config,
+    create_output_file, output,
     wallpaper::{self, detect_iynaixos},
 };
 use crate::{colors::get_term_colors, wallpaper::geom_from_str};
 
+#[cfg(feature = "nixos")]
 const NIX_COLOR1: [u8; 4] = [0x7e, 0xba, 0xe4, 255];
+#[cfg(feature = "nixos")]
 const NIX_COLOR2: [u8; 4] = [0x52, 0x77, 0xc3, 255];
 
-fn get_hyprland_scale() -> Option<f64> {
-    #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
-    #[serde(rename_all = "camelCase")]
-    pub struct HyprMonitor {
-        pub scale: f64,
-        pub focused: bool,
+/// size of an image logo, larger to fit the challenge when it is shown
+const fn image_size(args: &WFetchArgs, size: u32) -> u32 {
+    match args.image_size {
+        Some(size) => size,
+        None if args.challenge => size + 80,
+        None => size,
     }
-
-    // no scale arg provided, try getting it from hyprland
-    Command::new("hyprctl")
-        .arg("monitors")
-        .arg("-j")
-        .output()
-        .ok()
-        .and_then(|output| String::from_utf8(output.stdout).ok())
-        .and_then(|stdout| serde_json::from_str::<Vec<HyprMonitor>>(&stdout).ok())
-        .and_then(|monitors| monitors.into_iter().find(|m| m.focused))
-        .map(|monitor| monitor.scale)
-}
-
-fn get_niri_scale() -> Option<f64> {
-    #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
-    pub struct NiriMonitor {
-        pub logical: Option<NiriLogical>,
-    }
-
-    #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
-    pub struct NiriLogical {
-        pub scale: f64,
-    }
-
-    Command::new("niri")
-        .arg("msg")
-        .arg("--json")
-        .arg("focused-output")
-        .output()
-        .ok()
-        .and_then(|output| String::from_utf8(output.stdout).ok())
-        .and_then(|stdout| serde_json::from_str::<NiriMonitor>(&stdout).ok())
-        .and_then(|monitor| monitor.logical.map(|logical| logical.scale))
 }
 
 /// returns new sizes adjusted for the given scale
 fn resize_with_scale(scale: Option<f64>, width: u32, height: u32, term: &str) -> (u32, u32) {
-    // no scale arg, provided, try getting scale from hyprland or niri
+    // no scale arg, provided, try getting scale from the focused output
     let mut scale = scale
-        .or_else(get_hyprland_scale)
-        .or_else(get_niri_scale)
+        .or_else(|| output::focused().map(|output| output.scale))
         .unwrap_or(1.0);
 
     if term == "ghostty" || term.contains("wezterm") {
@@ -113,16 +85,13 @@ pub fn image_from_arg(arg: &str) -> Option<String> {
 }
 
 /// creates the wallpaper image that fastfetch will display
-pub fn resize_wallpaper(args: &WFetchArgs, term: &str, image_arg: &Option<String>) -> PathBuf {
+pub fn resize_wallpaper(args: &WFetchArgs, term: &str, image_arg: &str) -> PathBuf {
     let output = create_output_file("wfetch.png");
 
-    let wall = image_arg
-        .as_ref()
-        .and_then(|img| image_from_arg(img.as_str()))
-        .unwrap_or_else(|| {
-            eprintln!("Error: could not detect wallpaper!");
-            std::process::exit(1);
-        });
+    let wall = image_from_arg(image_arg).unwrap_or_else(|| {
+        eprintln!("Error: could not detect wallpaper!");
+        std::process::exit(1);
+    });
 
     ImageReader::open(&wall)
         .expect("could not open image")
@@ -160,10 +129,7 @@ pub fn resize_wallpaper(args: &WFetchArgs, term: &str, image_arg: &Option<String
         .decode()
         .expect("could not decode image");
 
-    let dst_size = args
-        .image_size
-        .unwrap_or(if args.challenge { 350 } else { 270 });
-
+    let dst_size = image_size(args, 270);
     let (dst_size, _) = resize_with_scale(args.scale, dst_size, dst_size, term);
 
     #[allow(clippy::cast_sign_loss)]
@@ -222,6 +188,274 @@ fn save_png(src: ImageBuffer<image::Rgba<u8>, Vec<u8>>, size: (u32, u32), output
         .unwrap_or_else(|_| panic!("failed to write png for {}", output.display()));
 }
 
+/// how an image logo is recolored with the terminal colors
+#[derive(Debug, Clone)]
+pub enum Recolor {
+    /// replaces the pixels close to each of the target colors
+    Colors(Vec<Rgba8>),
+    /// replaces the pixels within the black areas of each mask
+    Masks(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub enum LogoKind {
+    /// logo built into fastfetch, the logo of the current distro if no source is given
+    Builtin(Option<String>),
+    /// ascii art template using the color slots $1 to $4
+    Template(String),
+    /// image recolored with the terminal colors
+    Image {
+        source: String,
+        recolor: Recolor,
+        size: u32,
+        /// colors used when the terminal colors cannot be read, the image is left as is if empty
+        fallback: Vec<Rgba8>,
+    },
+    /// section of the wallpaper
+    Wallpaper,
+    /// section of the wallpaper in ascii
+    WallpaperAscii,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogoEntry {
+    pub name: String,
+    pub description: String,
+    pub kind: LogoKind,
+}
+
+impl LogoEntry {
+    fn new(name: &str, description: &str, kind: LogoKind) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            kind,
+        }
+    }
+}
+
+/// manifest of a user logo, read from `$XDG_CONFIG_HOME/wfetch/logos/<name>/logo.toml`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LogoManifest {
+    description: Option<String>,
+    /// image to recolor, relative to the logo directory
+    image: PathBuf,
+    /// masks where the black areas are recolored, relative to the logo directory
+    #[serde(default)]
+    masks: Vec<PathBuf>,
+    /// colors in the image that are recolored, as hex values
+    #[serde(default)]
+    colors: Vec<String>,
+    /// size of the image in pixels
+    size: Option<u32>,
+}
+
+impl LogoEntry {
+    /// reads a user logo from its directory, the directory name is the name of the logo
+    fn from_dir(dir: &Path) -> WFetchResult<Self> {
+        let path = dir.join("logo.toml");
+        let manifest: LogoManifest = toml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| format!("could not parse {}\n{e}", path.display()))?;
+
+        let file = |name: &Path| -> WFetchResult<String> {
+            let file = dir.join(name);
+            if !file.exists() {
+                return Err(format!("{} not found for {}", file.display(), path.display()).into());
+            }
+            Ok(file.to_string_lossy().to_string())
+        };
+
+        let recolor = match (manifest.masks.is_empty(), manifest.colors.is_empty()) {
+            (false, true) => Recolor::Masks(
+                manifest
+                    .masks
+                    .iter()
+                    .map(|mask| file(mask))
+                    .collect::<WFetchResult<_>>()?,
+            ),
+            (true, false) => Recolor::Colors(
+                manifest
+                    .colors
+                    .iter()
+                    .map(|color| parse_hex(color).map_err(|e| format!("{e} in {}", path.display())))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => {
+                return Err(
+                    format!("{} must have either `masks` or `colors`", path.display()).into(),
+                );
+            }
+        };
+
+        let name = dir
+            .file_name()
+            .expect("could not get logo directory name")
+            .to_string_lossy()
+            .to_string();
+
+        Ok(Self {
+            description: manifest
+                .description
+                .unwrap_or_else(|| format!("user logo from {}", dir.display())),
+            kind: LogoKind::Image {
+                source: file(&manifest.image)?,
+                recolor,
+                size: manifest.size.unwrap_or(270),
+                fallback: Vec::new(),
+            },
+            name,
+        })
+    }
+}
+
+fn parse_hex(color: &str) -> Result<Rgba8, String> {
+    let hex = color.trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("invalid color `{color}`, expected a hex value"));
+    }
+
+    Rgba8::from_str(hex).map_err(|e| e.to_string())
+}
+
+/// a user logo that could not be read
+#[derive(Debug, Clone)]
+pub struct InvalidLogo {
+    pub name: String,
+    pub error: String,
+}
+
+/// logos that can be selected with --logo
+#[derive(Debug, Clone)]
+pub struct Registry {
+    pub logos: Vec<LogoEntry>,
+    /// user logos with errors, which are only reported when they are selected
+    pub invalid: Vec<InvalidLogo>,
+}
+
+/// logos in `$XDG_CONFIG_HOME/wfetch/logos` sorted by name, and the logos that could not be read
+fn user_logos() -> (Vec<LogoEntry>, Vec<InvalidLogo>) {
+    let Ok(dirs) = std::fs::read_dir(Config::dir().join("logos")) else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let mut logos = Vec::new();
+    let mut invalid = Vec::new();
+    for dir in dirs
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|dir| dir.join("logo.toml").exists())
+    {
+        match LogoEntry::from_dir(&dir) {
+            Ok(logo) => logos.push(logo),
+            Err(e) => invalid.push(InvalidLogo {
+                name: dir
+                    .file_name()
+                    .expect("could not get logo directory name")
+                    .to_string_lossy()
+                    .to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    logos.sort_by(|a, b| a.name.cmp(&b.name));
+    invalid.sort_by(|a, b| a.name.cmp(&b.name));
+    (logos, invalid)
+}
+
+/// all logos that can be selected with --logo, user logos replace builtin logos with the same name
+///
+/// the registry is only read once, as it is used both to validate and to render the logo
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(read_registry)
+}
+
+fn read_registry() -> Registry {
+    let mut logos = vec![
+        LogoEntry::new(
+            "default",
+            "fastfetch logo for the current distro",
+            LogoKind::Builtin(None),
+        ),
+        LogoEntry::new(
+            "nixos",
+            "NixOS logo",
+            LogoKind::Builtin(Some("nixos".to_string())),
+        ),
+    ];
+
+    #[cfg(feature = "nixos")]
+    logos.extend([
+        LogoEntry::new(
+            "hollow",
+            "hollow NixOS logo",
+            LogoKind::Template(asset_path("nixos_hollow.txt")),
+        ),
+        LogoEntry::new(
+            "smooth",
+            "NixOS logo with smooth edges",
+            LogoKind::Template(asset_path("nixos_smooth.txt")),
+        ),
+        LogoEntry::new(
+            "waifu",
+            "waifu NixOS logo",
+            LogoKind::Image {
+                source: asset_path("nixos1.png"),
+                recolor: Recolor::Colors(vec![Rgba8::from(NIX_COLOR1), Rgba8::from(NIX_COLOR2)]),
+                size: 300,
+                fallback: vec![Rgba8::from(NIX_COLOR1), Rgba8::from(NIX_COLOR2)],
+            },
+        ),
+        LogoEntry::new(
+            "waifu2",
+            "waifu NixOS logo 2",
+            LogoKind::Image {
+                source: asset_path("nixos2.png"),
+                recolor: Recolor::Masks(vec![
+                    asset_path("nixos2-mask1.jpg"),
+                    asset_path("nixos2-mask2.jpg"),
+                ]),
+                size: 270,
+                fallback: vec![Rgba8::from(NIX_COLOR1), Rgba8::from(NIX_COLOR2)],
+            },
+        ),
+    ]);
+
+    logos.extend([
+        LogoEntry::new("wallpaper", "section of the wallpaper", LogoKind::Wallpaper),
+        LogoEntry::new(
+            "wallpaper-ascii",
+            "section of the wallpaper in ascii",
+            LogoKind::WallpaperAscii,
+        ),
+    ]);
+
+    let (user_logos, invalid) = user_logos();
+    logos.retain(|logo| {
+        !user_logos.iter().any(|user| user.name == logo.name)
+            && !invalid.iter().any(|user| user.name == logo.name)
+    });
+    logos.extend(user_logos);
+
+    Registry { logos, invalid }
+}
+
+/// finds a logo in the registry by name, a user logo with errors is an error
+pub fn find(name: &str) -> WFetchResult<Option<LogoEntry>> {
+    let registry = registry();
+    if let Some(logo) = registry.invalid.iter().find(|logo| logo.name == name) {
+        return Err(logo.error.clone().into());
+    }
+
+    Ok(registry
+        .logos
+        .iter()
+        .find(|logo| logo.name == name)
+        .cloned())
+}
+
 pub struct Logo {
     args: WFetchArgs,
     nixos: bool,
@@ -258,79 +492,112 @@ impl Logo {
         })
     }
 
-    pub fn waifu1(&self, color1: &Rgba8, color2: &Rgba8) -> JsonValue {
-        let output = create_output_file("wfetch.png");
-
-        let replace1 = Rgba8::from(NIX_COLOR1);
-        let replace2 = Rgba8::from(NIX_COLOR2);
-
-        let mut src = ImageReader::open(asset_path("nixos1.png"))
-            .expect("could not open nixos1.png")
-            .decode()
-            .expect("could not decode nixos1.png")
-            .into_rgba8();
-
-        let fuzz = 0.1 * (255.0_f64 * 255.0_f64 * 3.0_f64).sqrt();
-
-        for pixel in src.pixels_mut() {
-            if pixel.distance(replace1) < fuzz {
-                *pixel = color1.with_alpha(pixel[3]);
-            } else if pixel.distance(replace2) < fuzz {
-                *pixel = color2.with_alpha(pixel[3]);
-            }
+    /// colors for the logo from the terminal palette
+    fn term_colors(&self) -> Option<(Rgba8, Rgba8)> {
+        // tmux is skipped as the raw xterm sequences breaks rendering and text input
+        if self.tmux {
+            return None;
         }
 
-        let side = self
-            .args
-            .image_size
-            .unwrap_or(if self.args.challenge { 380 } else { 300 });
-
-        save_png(
-            src,
-            resize_with_scale(self.args.scale, side, side, &self.term),
-            &output,
-        );
+        get_term_colors()
+            .ok()
+            .map(|term_colors| colors::logo_colors(&term_colors))
+    }
 
-        self.with_backend(
-            output
-                .to_str()
-                .expect("could not convert output path to str"),
+    /// colors for the slots of ascii logos
+    fn ascii_colors(&self) -> JsonValue {
+        self.term_colors().map_or_else(
+            || {
+                json!({
+                    "1": "38;5;4", // blue
+                    "2": "38;5;6", // cyan
+                    "3": "48;5;6", // cyan bg
+                    "4": "48;5;4", // blue bg
+                })
+            },
+            |(color1, color2)| {
+                json!({
+                    "1": color1.term_fg(),
+                    "2": color2.term_fg(),
+                    "3": color2.term_bg(),
+                    "4": color1.term_bg(),
+                })
+            },
         )
     }
 
-    pub fn waifu2(&self, color1: &Rgba8, color2: &Rgba8) -> JsonValue {
+    pub fn recolor_image(
+        &self,
+        source: &str,
+        recolor: &Recolor,
+        default_size: u32,
+        fallback: &[Rgba8],
+    ) -> JsonValue {
         let output = create_output_file("wfetch.png");
 
-        let mut src = ImageReader::open(asset_path("nixos2.png"))
-            .expect("could not open nixos2.png")
+        let slots = match recolor {
+            Recolor::Colors(targets) => targets.len(),
+            Recolor::Masks(masks) => masks.len(),
+        };
+
+        // the logo colors alternate if there are more than two colors or masks
+        let colors: Vec<_> = self.term_colors().map_or_else(
+            || fallback.to_vec(),
+            |pair| {
+                <[Rgba8; 2]>::from(pair)
+                    .into_iter()
+                    .cycle()
+                    .take(slots)
+                    .collect()
+            },
+        );
+
+        let mut src = ImageReader::open(source)
+            .unwrap_or_else(|_| panic!("could not open {source}"))
             .decode()
-            .expect("could not decode nixos2.png")
+            .unwrap_or_else(|_| panic!("could not decode {source}"))
             .into_rgba8();
 
-        let mask1 = image::open(asset_path("nixos2-mask1.jpg"))
-            .expect("could not open mask1")
-            .to_rgba8();
-        let mask2 = image::open(asset_path("nixos2-mask2.jpg"))
-            .expect("could not open mask2")
-            .to_rgba8();
-
         let fuzz = 0.1 * (255.0_f64 * 255.0_f64 * 3.0_f64).sqrt();
-        let black = Rgba([0, 0, 0, 255]);
 
-        for (x, y, pixel) in src.enumerate_pixels_mut() {
-            if black.distance(pixel.multiply(*mask1.get_pixel(x, y))) < fuzz {
-                *pixel = color1.with_alpha(pixel[3]);
+        match recolor {
+            Recolor::Colors(targets) => {
+                for pixel in src.pixels_mut() {
+                    if let Some(color) = targets.iter().zip(&colors).find_map(|(target, color)| {
+                        (pixel.distance(*target) < fuzz).then_some(color)
+                    }) {
+                        *pixel = color.with_alpha(pixel[3]);
+                    }
+                }
             }
-
-            if black.distance(pixel.multiply(*mask2.get_pixel(x, y))) < fuzz {
-                *pixel = color2.with_alpha(pixel[3]);
+            Recolor::Masks(masks) => {
+                let masks: Vec<_> = masks
+                    .iter()
+                    .map(|mask| {
+                        let mask_img = image::open(mask)
+                            .unwrap_or_else(|_| panic!("could not open {mask}"))
+                            .to_rgba8();
+
+                        if mask_img.dimensions() != src.dimensions() {
+                            eprintln!("Error: {mask} is not the same size as {source}");
+                            std::process::exit(1);
+                        }
+                        mask_img
+                    })
+                    .collect();
+                let black = Rgba([0, 0, 0, 255]);
+
+                for (x, y, pixel) in src.enumerate_pixels_mut() {
+                    for (mask, color) in masks.iter().zip(&colors) {
+                        if black.distance(pixel.multiply(*mask.get_pixel(x, y))) < fuzz {
+                            *pixel = color.with_alpha(pixel[3]);
+                        }
+                    }
+                }
             }
         }
 
-        let side = self
-            .args
-            .image_size
-            .unwrap_or(if self.args.challenge { 350 } else { 270 });
+        let side = image_size(&self.args, default_size);
 
         save_png(
             src,
@@ -346,7 +613,7 @@ impl Logo {
     }
 
     /// creates the wallpaper ascii that fastfetch will display
-    pub fn show_wallpaper_ascii(&self, image_arg: &Option<String>) -> PathBuf {
+    pub fn show_wallpaper_ascii(&self, image_arg: &str) -> PathBuf {
         let img = resize_wallpaper(&self.args, &self.term, image_arg);
         let output_dir = img.parent().expect("could not get output dir");
 
@@ -372,167 +639,52 @@ impl Logo {
         output_dir.join("wfetch-ascii-art.txt")
     }
 
-    pub fn waifu1_default(&self) -> JsonValue {
-        self.waifu1(&Rgba8::from(NIX_COLOR1), &Rgba8::from(NIX_COLOR2))
-    }
-
-    pub fn waifu2_default(&self) -> JsonValue {
-        self.waifu2(&Rgba8::from(NIX_COLOR1), &Rgba8::from(NIX_COLOR2))
-    }
-
-    pub fn hollow_default(&self) -> JsonValue {
-        json!({
-            "source": asset_path("nixos_hollow.txt"),
-            "color": json!({ "1": "blue", "2": "cyan" }),
-        })
-    }
-
-    pub fn smooth_default(&self) -> JsonValue {
-        json!({
-            "source": asset_path("nixos_smooth.txt"),
-            "color": json!({
-                "1": "38;5;4", // blue
-                "2": "38;5;6", // cyan
-                "2": "48;5;6", // blue bg
-                "2": "48;5;4", // cyan by
-             }),
-        })
-    }
-
-    pub fn filled_default(&self) -> JsonValue {
-        json!({
-            "source": "nixos",
-            "color": json!({
-                "1": "38;5;4", // blue
-                "2": "38;5;6", // cyan
-                "2": "48;5;6", // blue bg
-                "2": "48;5;4", // cyan by
-             }),
-        })
-    }
-
-    pub fn module_for_tmux(&self) -> JsonValue {
-        #[cfg(feature = "nixos")]
-        if self.args.waifu {
-            return self.waifu1_default();
-        }
-        #[cfg(feature = "nixos")]
-        if self.args.waifu2 {
-            return self.waifu2_default();
-        }
-
-        #[cfg(feature = "nixos")]
-        if self.args.hollow {
-            return self.hollow_default();
-        }
-
-        #[cfg(feature = "nixos")]
-        if self.args.smooth {
-            return self.smooth_default();
-        }
-
-        if self.nixos {
-            return self.filled_default();
-        }
-
-        // use fastfetch default
-        json!({ "source": null })
-    }
-
     pub fn module(&self) -> JsonValue {
-        if self.args.wallpaper_ascii.is_some() {
-            let ascii_file = self.show_wallpaper_ascii(&self.args.wallpaper_ascii);
-            return json!({
-                "type": "auto",
-                "source": ascii_file.to_str().expect("could not convert ascii file path to str"),
-            });
-        }
+        let name =
+            self.args
+                .logo
+                .as_deref()
+                .unwrap_or(if self.nixos { "nixos" } else { "default" });
+
+        let logo = match find(name) {
+            Ok(Some(logo)) => logo,
+            Ok(None) => {
+                eprintln!("Error: unknown logo `{name}`, see --list-logos");
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        };
 
-        if self.args.wallpaper.is_some() {
-            return self.with_backend(
-                resize_wallpaper(&self.args, &self.term, &self.args.wallpaper)
+        // detect the wallpaper if no image is given
+        let image_arg = self.args.image.as_deref().unwrap_or_default();
+
+        match logo.kind {
+            LogoKind::Builtin(None) => json!({ "source": null }),
+            LogoKind::Builtin(Some(source)) | LogoKind::Template(source) => json!({
+                "source": source,
+                "color": self.ascii_colors(),
+            }),
+            LogoKind::Image {
+                source,
+                recolor,
+                size,
+                fallback,
+            } => self.recolor_image(&source, &recolor, size, &fallback),
+            LogoKind::Wallpaper => self.with_backend(
+                resize_wallpaper(&self.args, &self.term, image_arg)
                     .to_str()
                     .expect("could not convert output path to str"),
-            );
-        }
-
-        // handle tmux separately as the raw xterm sequences breaks rendering and text input
-        if self.tmux {
-            return self.module_for_tmux();
-        }
-
-        match get_term_colors() {
-            Err(_) => {
-                #[cfg(feature = "nixos")]
-                {
-                    if self.args.waifu {
-                        return self.waifu1_default();
-                    }
-
-                    if self.args.waifu2 {
-                        return self.waifu2_default();
-                    }
-
-                    if self.args.hollow {
-                        return self.hollow_default();
-                    }
-
-                    if self.args.smooth {
-                        return self.smooth_default();
-                    }
-
-                    if self.nixos {
-                        return self.filled_default();
-                    }
-                }
-            }
-
-            Ok(term_colors) => {
-                // remove background color to get contrast
-                let (color1, color2) = colors::most_contrasting_pair(&term_colors[1..]);
-
-                #[cfg(feature = "nixos")]
-                if self.args.waifu {
-                    return self.waifu1(&color1, &color2);
-                }
-                #[cfg(feature = "nixos")]
-                if self.args.waifu2 {
-                    return self.waifu2(&color1, &color2);
-                }
-
-                let source_colors = json!({
-                    "1": color1.term_fg(),
-                    "2": color2.term_fg(),
-                    "3": color2.term_bg(),
-                    "4": color1.term_bg(),
-                });
-
-                #[cfg(feature = "nixos")]
-                if self.args.hollow {
-                    return json!({
-                        "source": asset_path("nixos_hollow.txt"),
-                        "color": source_colors,
-                    });
-                }
-
-                #[cfg(feature = "nixos")]
-                if self.args.smooth {
-                    return json!({
-                        "source": asset_path("nixos_smooth.txt"),
-                        "color": source_colors,
-                    });
-                }
-
-                if self.nixos {
-                    return json!({
-                        "source": "nixos",
-                        "color": source_colors,
-                    });
-                }
+            ),
+            LogoKind::WallpaperAscii => {
+                let ascii_file = self.show_wallpaper_ascii(image_arg);
+                json!({
+                    "type": "auto",
+                    "source": ascii_file.to_str().expect("could not convert ascii file path to str"),
+                })
             }
         }
-
-        // use fastfetch default
-        json!({ "source": null })
     }
 }
@@ -1,4 +1,5 @@
-use clap::Parser;
+use chrono::{DateTime, FixedOffset};
+use clap::{CommandFactory, FromArgMatches};
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
     terminal::{Clear, ClearType},
@@ -14,14 +15,18 @@ use std::{
     time::Duration,
 };
 use wfetch::{
-    cli::{generate_completions, WFetchArgs},
-    create_output_file, Fastfetch,
+    Fastfetch,
+    challenge::{self, Challenge},
+    checkin,
+    cli::{ChallengeCommand, Command as WFetchCommand, WFetchArgs, generate_completions},
+    config::Config,
+    create_output_file, logos,
 };
 
-fn wfetch(args: &WFetchArgs) {
+fn wfetch(args: &WFetchArgs, config: &Config) {
     let config_jsonc = create_output_file("wfetch.jsonc");
 
-    Fastfetch::new(args).create_config(&config_jsonc);
+    Fastfetch::new(args, config).create_config(&config_jsonc);
 
     Command::new("fastfetch")
         .arg("--hide-cursor")
@@ -32,19 +37,147 @@ fn wfetch(args: &WFetchArgs) {
         .expect("failed to run fastfetch");
 }
 
+/// records a check-in for the challenge, or for all challenges
+fn checkin(
+    args: &WFetchArgs,
+    config: &Config,
+    now: DateTime<FixedOffset>,
+    challenge: Option<&str>,
+    note: Option<&str>,
+) {
+    if let Some(name) = challenge {
+        let challenges = if config.challenges.is_empty() {
+            Challenge::from_args(args).into_iter().collect()
+        } else {
+            config.challenges.clone()
+        };
+
+        if !challenges.iter().any(|c| c.is_named(name)) {
+            let available: Vec<_> = challenges.iter().flat_map(Challenge::names).collect();
+            if available.is_empty() {
+                eprintln!(
+                    "Error: unknown challenge `{name}`, challenges need a title or type to check in to them"
+                );
+            } else {
+                eprintln!(
+                    "Error: unknown challenge `{name}`, available challenges: {}",
+                    available.join(", ")
+                );
+            }
+            std::process::exit(1);
+        }
+    }
+
+    let checkin = checkin::record(now, challenge.map(String::from), note.map(String::from))
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        });
+
+    println!(
+        "Checked in to {} on {}",
+        challenge.unwrap_or("all challenges"),
+        checkin.time.date_naive()
+    );
+}
+
+/// maps the deprecated logo flags to --logo and --image, these replace the logo from the config
+fn deprecated_logo(args: &mut WFetchArgs) {
+    #[allow(unused_mut)]
+    let mut flags = vec![
+        ("wallpaper", args.wallpaper.take()),
+        ("wallpaper-ascii", args.wallpaper_ascii.take()),
+    ];
+    #[cfg(feature = "nixos")]
+    flags.extend([
+        ("hollow", args.hollow.then(String::new)),
+        ("smooth", args.smooth.then(String::new)),
+        ("waifu", args.waifu.then(String::new)),
+        ("waifu2", args.waifu2.then(String::new)),
+    ]);
+
+    let Some((name, image)) = flags
+        .into_iter()
+        .find_map(|(name, image)| image.map(|image| (name, image)))
+    else {
+        return;
+    };
+
+    if image.is_empty() {
+        eprintln!("Warning: --{name} is deprecated, use --logo {name}");
+    } else {
+        eprintln!(
+            "Warning: --{name} <WALLPAPER> is deprecated, use --logo {name} --image <WALLPAPER>"
+        );
+        args.image = Some(image);
+    }
+    args.logo = Some(name.to_string());
+}
+
 fn main() {
-    let args = WFetchArgs::parse();
+    let matches = WFetchArgs::command().get_matches();
+    let mut args = WFetchArgs::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    // print shell completions
+    // print shell completions, not exclusive as WFETCH_* variables in the environment would conflict
     if let Some(shell) = args.generate {
         return generate_completions(&shell);
     }
 
+    if args.list_logos {
+        let registry = logos::registry();
+        for logo in &registry.logos {
+            println!("{:<16} {}", logo.name, logo.description);
+        }
+        for logo in &registry.invalid {
+            eprintln!("Warning: skipping logo `{}`\n{}", logo.name, logo.error);
+        }
+        return;
+    }
+
+    // precedence is config file < WFETCH_* environment variables < command line arguments
+    let mut config = Config::load(args.profile.as_deref()).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+    config.apply(&mut args, &matches);
+    deprecated_logo(&mut args);
+
+    if let Err(e) = Challenge::from_args(&args) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+
+    let now = challenge::now(&args).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+
+    if let Some(WFetchCommand::Challenge {
+        command: ChallengeCommand::Checkin { challenge, note },
+    }) = &args.command
+    {
+        return checkin(&args, &config, now, challenge.as_deref(), note.as_deref());
+    }
+
+    if let Some(name) = &args.logo {
+        match logos::find(name) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                eprintln!("Error: unknown logo `{name}`, see --list-logos");
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
     crossterm::execute!(stdout(), Clear(ClearType::All), MoveTo(0, 0))
         .expect("Failed to clear screen");
 
     // initial display of wfetch
-    wfetch(&args);
+    wfetch(&args, &config);
 
     // not showing waifu / wallpaper, no need to wait for signal
     if !args.listen {
@@ -65,7 +198,7 @@ fn main() {
                     std::process::exit(0);
                 }
                 SIGUSR2 => {
-                    wfetch(&args);
+                    wfetch(&args, &config);
                 }
                 _ => unreachable!(),
             }
@@ -0,0 +1,94 @@
+use std::process::{Command, Stdio};
+
+use serde::Deserialize;
+
+/// the focused output of the compositor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub name: String,
+    pub scale: f64,
+}
+
+fn focused_hyprland() -> Option<Output> {
+    #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct HyprMonitor {
+        pub name: String,
+        pub scale: f64,
+        pub focused: bool,
+    }
+
+    Command::new("hyprctl")
+        .arg("monitors")
+        .arg("-j")
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|stdout| serde_json::from_str::<Vec<HyprMonitor>>(&stdout).ok())
+        .and_then(|monitors| monitors.into_iter().find(|m| m.focused))
+        .map(|monitor| Output {
+            name: monitor.name,
+            scale: monitor.scale,
+        })
+}
+
+fn focused_niri() -> Option<Output> {
+    #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+    pub struct NiriMonitor {
+        pub name: String,
+        pub logical: Option<NiriLogical>,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
+    pub struct NiriLogical {
+        pub scale: f64,
+    }
+
+    Command::new("niri")
+        .arg("msg")
+        .arg("--json")
+        .arg("focused-output")
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|stdout| serde_json::from_str::<NiriMonitor>(&stdout).ok())
+        .and_then(|monitor| {
+            monitor.logical.map(|logical| Output {
+                name: monitor.name,
+                scale: logical.scale,
+            })
+        })
+}
+
+/// only the name is used from sway, the scale is not used for resizing
+fn focused_sway() -> Option<String> {
+    #[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+    pub struct SwayOutput {
+        pub name: String,
+        pub focused: bool,
+    }
+
+    Command::new("swaymsg")
+        .arg("-t")
+        .arg("get_outputs")
+        .arg("-r")
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|stdout| serde_json::from_str::<Vec<SwayOutput>>(&stdout).ok())
+        .and_then(|outputs| outputs.into_iter().find(|o| o.focused))
+        .map(|output| output.name)
+}
+
+/// the focused output from hyprland or niri
+pub fn focused() -> Option<Output> {
+    focused_hyprland().or_else(focused_niri)
+}
+
+/// name of the focused output from hyprland, niri or sway
+pub fn focused_name() -> Option<String> {
+    focused().map(|output| output.name).or_else(focused_sway)
+}
@@ -1,9 +1,11 @@
 use std::{
+    cell::OnceCell,
+    collections::HashMap,
     path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 
-use crate::{CommandUtf8, full_path};
+use crate::{CommandUtf8, full_path, output};
 
 /// detect wallpaper using current-wallpaper file in tmpfs
 pub fn detect_iynaixos() -> Option<String> {
@@ -40,89 +42,518 @@ pub fn info(image: &str, fallback: (f64, f64, f64, f64)) -> (f64, f64, f64, f64)
     )
 }
 
+/// wallpaper for the output from (output, path) pairs, falling back to the wallpaper for all
+/// outputs, then the first wallpaper
+fn for_output(wallpapers: Vec<(String, String)>, output: Option<&str>) -> Option<String> {
+    let position = |matches: &dyn Fn(&str) -> bool| {
+        wallpapers
+            .iter()
+            .position(|(name, _)| matches(name.as_str()))
+    };
+
+    let index = output
+        .and_then(|output| position(&|name| name == output))
+        .or_else(|| position(&|name| name.is_empty() || name == "*"))
+        .unwrap_or_default();
+
+    wallpapers
+        .into_iter()
+        .nth(index)
+        .map(|(_, wallpaper)| wallpaper)
+}
+
 /// detect wallpaper using swwww
-fn detect_swww() -> Option<String> {
-    Command::new("swww")
+fn detect_swww(output: Option<&str>) -> Option<String> {
+    // e.g. `DP-1: 2560x1440, scale: 1, currently displaying: image: /path/to/wallpaper.png`
+    let wallpapers = Command::new("swww")
         .arg("query")
         .execute_stdout_lines()
-        .first()
-        .and_then(|wallpaper| wallpaper.rsplit_once("image: "))
-        .map(|(_, wallpaper)| wallpaper.trim().trim_matches('\'').to_string())
-        .filter(|wallpaper| !wallpaper.is_empty() && wallpaper != "STDIN")
+        .iter()
+        .filter_map(|line| {
+            let (name, _) = line.trim_start_matches(':').trim().split_once(':')?;
+            let (_, wallpaper) = line.rsplit_once("image: ")?;
+            let wallpaper = wallpaper.trim().trim_matches('\'');
+            (!wallpaper.is_empty() && wallpaper != "STDIN")
+                .then(|| (name.to_string(), wallpaper.to_string()))
+        })
+        .collect();
+
+    for_output(wallpapers, output)
 }
 
-/// detect wallpaper using swaybg
-fn detect_swaybg() -> Option<String> {
+/// detect wallpaper using swaybg, which sets the image for each output with `-o <output> -i <image>`
+fn detect_swaybg(output: Option<&str>) -> Option<String> {
     let sys = sysinfo::System::new_all();
 
-    let mut processes = sys.processes_by_exact_name("swaybg".as_ref());
-    processes
-        .find_map(|process| process.cmd().last().cloned())
-        .and_then(|wallpaper| wallpaper.into_string().ok())
+    let wallpapers = sys
+        .processes_by_exact_name("swaybg".as_ref())
+        .flat_map(|process| {
+            let args: Vec<_> = process
+                .cmd()
+                .iter()
+                .filter_map(|arg| arg.to_str())
+                .collect();
+
+            let mut wallpapers = Vec::new();
+            let mut name = String::new();
+            for pair in args.windows(2) {
+                match pair[0] {
+                    "-o" | "--output" => pair[1].clone_into(&mut name),
+                    "-i" | "--image" => wallpapers.push((name.clone(), pair[1].to_string())),
+                    _ => {}
+                }
+            }
+            wallpapers
+        })
+        .collect();
+
+    for_output(wallpapers, output)
+}
+
+/// wallpapers from hyprpaper as (monitor, path), an empty monitor is the wallpaper for all monitors
+fn hyprpaper_active() -> Vec<(String, String)> {
+    Command::new("hyprctl")
+        .args(["hyprpaper", "listactive"])
+        .stderr(Stdio::null())
+        .execute_stdout_lines()
+        .iter()
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(monitor, wallpaper)| (monitor.trim().to_string(), wallpaper.trim().to_string()))
+        .filter(|(_, wallpaper)| wallpaper.starts_with('/'))
+        .collect()
+}
+
+/// removes a `#` comment from a hyprlang line, `##` is an escaped `#`
+fn strip_hyprlang_comment(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '#' {
+            if chars.peek() != Some(&'#') {
+                break;
+            }
+            chars.next();
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// parses the hyprpaper config as (monitor, path), following `source` includes
+fn hyprpaper_config(
+    path: &Path,
+    variables: &mut HashMap<String, String>,
+    wallpapers: &mut Vec<(String, String)>,
+    depth: usize,
+) {
+    // guard against configs that source each other
+    if depth > 8 {
+        return;
+    }
+
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    // monitor and path of the current wallpaper block
+    let mut block: Option<(String, Option<String>)> = None;
+
+    for line in contents.lines() {
+        let line = strip_hyprlang_comment(line);
+        let line = line.trim();
+
+        if line.starts_with("wallpaper") && line.ends_with('{') {
+            block = Some((String::new(), None));
+            continue;
+        }
+
+        if line == "}" {
+            if let Some((monitor, Some(path))) = block.take() {
+                wallpapers.push((monitor, path));
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        // longer names first, so `$wall` does not replace the start of `$wallpaper`
+        let mut names: Vec<_> = variables.iter().collect();
+        names.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+        let value = names
+            .into_iter()
+            .fold(value.trim().to_string(), |value, (name, replacement)| {
+                value.replace(&format!("${name}"), replacement)
+            });
+
+        if let Some(name) = key.strip_prefix('$') {
+            variables.insert(name.to_string(), value);
+            continue;
+        }
+
+        match (&mut block, key) {
+            (Some((monitor, _)), "monitor") => *monitor = value,
+            (Some((_, path)), "path") => {
+                *path = Some(full_path(&value).to_string_lossy().to_string());
+            }
+            // relative paths are relative to the config that sources them
+            (None, "source") => {
+                let source = full_path(&value);
+                let source = match path.parent() {
+                    Some(dir) if source.is_relative() => dir.join(source),
+                    _ => source,
+                };
+                hyprpaper_config(&source, variables, wallpapers, depth + 1);
+            }
+            // old syntax, e.g. `wallpaper = DP-1,contain:~/wallpaper.png`
+            (None, "wallpaper") => {
+                if let Some((monitor, path)) = value.split_once(',') {
+                    let path = ["contain:", "tile:", "cover:", "fill:"]
+                        .iter()
+                        .find_map(|mode| path.trim().strip_prefix(mode))
+                        .unwrap_or(path)
+                        .trim();
+                    wallpapers.push((
+                        monitor.trim().to_string(),
+                        full_path(path).to_string_lossy().to_string(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// wallpapers from hyprpaper as (monitor, path), from the running hyprpaper or the config
+fn hyprpaper_wallpapers() -> Vec<(String, String)> {
+    let active = hyprpaper_active();
+    if !active.is_empty() {
+        return active;
+    }
+
+    let mut wallpapers = Vec::new();
+    hyprpaper_config(
+        &full_path("~/.config/hypr/hyprpaper.conf"),
+        &mut HashMap::new(),
+        &mut wallpapers,
+        0,
+    );
+    wallpapers
 }
 
 /// detect wallpaper using hyprpaper
-fn detect_hyprpaper() -> Option<String> {
-    std::fs::read_to_string(full_path("~/.config/hypr/hyprpaper.conf"))
+fn detect_hyprpaper(output: Option<&str>) -> Option<String> {
+    for_output(hyprpaper_wallpapers(), output)
+}
+
+/// splits a shell command into arguments, handling quotes and escapes
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"') | None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.get_or_insert_default().push(escaped);
+                }
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (_, c) => word.get_or_insert_default().push(c),
+        }
+    }
+
+    words.extend(word);
+    words
+}
+
+/// detect wallpaper using feh, the first image from the command in ~/.fehbg
+fn detect_feh() -> Option<String> {
+    std::fs::read_to_string(full_path("~/.fehbg"))
+        .ok()?
+        .lines()
+        .map(shell_words)
+        .find(|words| words.first().is_some_and(|cmd| cmd.ends_with("feh")))?
+        .into_iter()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-') && Path::new(arg).is_file())
+}
+
+/// detect wallpaper using nitrogen, the image for the first screen
+fn detect_nitrogen() -> Option<String> {
+    std::fs::read_to_string(full_path("~/.config/nitrogen/bg-saved.cfg"))
         .ok()?
         .lines()
-        .find(|line| line.trim().starts_with("wallpaper"))?
-        .rsplit_once(',')?
-        .1
-        .trim()
-        .to_string()
-        .into()
+        .find_map(|line| line.trim().strip_prefix("file="))
+        .map(|wallpaper| full_path(wallpaper.trim()).to_string_lossy().to_string())
+}
+
+/// reads a gsettings key, without the quotes and file:// prefix of uris
+fn gsettings(schema: &str, key: &str) -> Option<String> {
+    Command::new("gsettings")
+        .arg("get")
+        .arg(schema)
+        .arg(key)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .map(|output| String::from_utf8(output.stdout).unwrap_or_default())
+        .map(|wallpaper| {
+            let wallpaper = wallpaper.trim().trim_matches('\'');
+            wallpaper
+                .strip_prefix("file://")
+                .unwrap_or(wallpaper)
+                .to_string()
+        })
+}
+
+/// true if one of the desktops is in `XDG_CURRENT_DESKTOP`, e.g. `Budgie:GNOME`
+fn is_current_desktop(desktops: &[&str]) -> bool {
+    std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|current| {
+        current.split(':').any(|name| {
+            desktops
+                .iter()
+                .any(|desktop| name.eq_ignore_ascii_case(desktop))
+        })
+    })
+}
+
+/// true if `XDG_CURRENT_DESKTOP` is set and is none of the desktops
+fn is_other_desktop(desktops: &[&str]) -> bool {
+    std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|current| !current.is_empty())
+        && !is_current_desktop(desktops)
 }
 
 /// detect wallpaper using gsettings (gnome, cinnamon, mate)
 fn detect_gsettings() -> Option<String> {
+    // the gnome schema is often installed on other desktops, with a default wallpaper that exists,
+    // but outside of a desktop session, e.g. a tty or systemd unit, the desktop is unknown
+    if is_other_desktop(&[
+        "gnome",
+        "unity",
+        "pantheon",
+        "cinnamon",
+        "x-cinnamon",
+        "mate",
+    ]) {
+        return None;
+    }
+
     [
         ("org.gnome.desktop.background", "picture-uri"),
         ("org.cinnamon.desktop.background", "picture-uri"),
         ("org.mate.background", "picture-filename"),
     ]
     .iter()
-    .find_map(|(gdir, gkey)| {
-        Command::new("gsettings")
-            .arg("get")
-            .arg(gdir)
-            .arg(gkey)
-            .stdout(Stdio::piped())
+    .find_map(|(schema, key)| gsettings(schema, key))
+}
+
+/// detect wallpaper for budgie, which uses the dark wallpaper of the gnome keys with a dark color scheme
+fn detect_budgie() -> Option<String> {
+    if !is_current_desktop(&["budgie"]) {
+        return None;
+    }
+
+    let dark = gsettings("org.gnome.desktop.interface", "color-scheme")
+        .is_some_and(|scheme| scheme == "prefer-dark");
+
+    dark.then(|| gsettings("org.gnome.desktop.background", "picture-uri-dark"))
+        .flatten()
+        .filter(|wallpaper| !wallpaper.is_empty())
+        .or_else(|| gsettings("org.gnome.desktop.background", "picture-uri"))
+}
+
+/// detect wallpaper for xfce, using the last image of the output, or the first monitor and
+/// workspace by name
+fn detect_xfce(output: Option<&str>) -> Option<String> {
+    if !is_current_desktop(&["xfce"]) {
+        return None;
+    }
+
+    let mut properties: Vec<_> = Command::new("xfconf-query")
+        .args(["--channel", "xfce4-desktop", "--list"])
+        .stderr(Stdio::null())
+        .execute_stdout_lines()
+        .into_iter()
+        .filter(|property| property.ends_with("/last-image"))
+        .collect();
+    properties.sort();
+
+    // properties are e.g. /backdrop/screen0/monitorDP-1/workspace0/last-image
+    if let Some(output) = output {
+        let monitor = format!("/monitor{output}/");
+        properties.sort_by_key(|property| !property.contains(&monitor));
+    }
+
+    properties.iter().find_map(|property| {
+        Command::new("xfconf-query")
+            .args(["--channel", "xfce4-desktop", "--property", property])
             .stderr(Stdio::null())
-            .output()
-            .ok()
-            .map(|output| String::from_utf8(output.stdout).unwrap_or_default())
-            .map(|wallpaper| {
-                let wallpaper = wallpaper.trim();
-                wallpaper
-                    .trim_matches('\'')
-                    .strip_prefix("file://")
-                    .unwrap_or(wallpaper)
-                    .to_string()
-            })
+            .execute_stdout_lines()
+            .first()
+            .map(|wallpaper| wallpaper.trim().to_string())
+            .filter(|wallpaper| !wallpaper.is_empty())
+    })
+}
+
+/// detect wallpaper for lxqt, using the desktop settings of pcmanfm-qt
+fn detect_lxqt() -> Option<String> {
+    if !is_current_desktop(&["lxqt"]) {
+        return None;
+    }
+
+    ["lxqt", "default"].iter().find_map(|profile| {
+        std::fs::read_to_string(full_path(format!(
+            "~/.config/pcmanfm-qt/{profile}/settings.conf"
+        )))
+        .ok()?
+        .lines()
+        .skip_while(|line| line.trim() != "[Desktop]")
+        .skip(1)
+        .take_while(|line| !line.trim().starts_with('['))
+        .find_map(|line| line.trim().strip_prefix("Wallpaper="))
+        .map(|wallpaper| full_path(wallpaper.trim()).to_string_lossy().to_string())
     })
 }
 
-/// detect wallpaper for plasma
+/// sections of an ini file by their full header, e.g. `[Containments][1]`
+fn ini_sections(contents: &str) -> Vec<(String, HashMap<String, String>)> {
+    let mut sections: Vec<(String, HashMap<String, String>)> = Vec::new();
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            sections.push((line.to_string(), HashMap::new()));
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some((_, section)) = sections.last_mut()
+        {
+            section.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    sections
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            ["jpg", "jpeg", "png", "webp", "avif"].contains(&ext.to_lowercase().as_str())
+        })
+}
+
+/// resolves a plasma wallpaper package directory to its largest image
+fn resolve_plasma_image(wallpaper: &str) -> String {
+    let wallpaper = wallpaper.strip_prefix("file://").unwrap_or(wallpaper);
+    let images = Path::new(wallpaper).join("contents").join("images");
+
+    // images in the package are named by their resolution, e.g. 1920x1080.png
+    let resolution = |path: &PathBuf| {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.split_once('x'))
+            .and_then(|(w, h)| Some(w.parse::<u64>().ok()? * h.parse::<u64>().ok()?))
+            .unwrap_or_default()
+    };
+
+    std::fs::read_dir(images)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_image(path))
+        .max_by_key(resolution)
+        .map_or_else(
+            || wallpaper.to_string(),
+            |image| image.to_string_lossy().to_string(),
+        )
+}
+
+/// detect wallpaper for plasma using the config of the desktop containment for the current
+/// activity on the first screen
+fn detect_plasma_appletsrc() -> Option<String> {
+    let sections = ini_sections(
+        &std::fs::read_to_string(full_path(
+            "~/.config/plasma-org.kde.plasma.desktop-appletsrc",
+        ))
+        .ok()?,
+    );
+
+    let activity = std::fs::read_to_string(full_path("~/.config/kactivitymanagerdrc"))
+        .ok()
+        .and_then(|contents| {
+            ini_sections(&contents)
+                .into_iter()
+                .find(|(header, _)| header == "[main]")
+                .and_then(|(_, mut main)| main.remove("currentActivity"))
+        });
+
+    // the desktop containment with the lowest screen, panels and other containments without
+    // a wallpaper image are skipped
+    let (_, _, image) = sections
+        .iter()
+        .filter_map(|(header, keys)| {
+            let id = header.strip_prefix("[Containments][")?.strip_suffix(']')?;
+            // only the containment itself, not its nested groups
+            let id = id.parse::<u32>().ok()?;
+
+            let plugin = keys.get("wallpaperplugin")?;
+            if let Some(activity) = &activity
+                && keys.get("activityId") != Some(activity)
+            {
+                return None;
+            }
+
+            // slideshows only have an image once the current slide has been saved, otherwise
+            // the current slide is unknown and detection moves on
+            let general = format!("[Containments][{id}][Wallpaper][{plugin}][General]");
+            let image = sections
+                .iter()
+                .find(|(header, _)| *header == general)?
+                .1
+                .get("Image")
+                .filter(|image| !image.is_empty())?;
+
+            let screen = keys
+                .get("lastScreen")
+                .and_then(|screen| screen.parse::<i32>().ok())
+                .unwrap_or(i32::MAX);
+            Some((screen, id, image))
+        })
+        .min()?;
+
+    Some(resolve_plasma_image(image))
+}
+
+/// detect wallpaper for plasma, with qdbus6 or qdbus, or the plasma config if neither is available
 fn detect_plasma() -> Option<String> {
-    let plasma_script = r#"print(desktops().map(d => {d.currentConfigGroup=["Wallpaper", "org.kde.image", "General"]; return d.readConfig("Image")}).join("\n"))"#;
-    Command::new("qdbus")
-        .arg("org.kde.plasmashell")
-        .arg("/PlasmaShell")
-        .arg("org.kde.PlasmaShell.evaluateScript")
-        .arg(plasma_script)
-        .execute_stdout_lines()
-        .first()
-        .map(|wallpaper| {
-            wallpaper
-                .strip_prefix("file://")
-                .unwrap_or(wallpaper)
-                .to_string()
+    let plasma_script = r#"print(desktops().map(d => {d.currentConfigGroup=["Wallpaper", d.wallpaperPlugin, "General"]; return d.readConfig("Image")}).join("\n"))"#;
+    ["qdbus6", "qdbus"]
+        .iter()
+        .find_map(|qdbus| {
+            Command::new(qdbus)
+                .arg("org.kde.plasmashell")
+                .arg("/PlasmaShell")
+                .arg("org.kde.PlasmaShell.evaluateScript")
+                .arg(plasma_script)
+                .stderr(Stdio::null())
+                .execute_stdout_lines()
+                .into_iter()
+                .find(|wallpaper| !wallpaper.trim().is_empty())
         })
+        .map(|wallpaper| resolve_plasma_image(wallpaper.trim()))
+        .or_else(detect_plasma_appletsrc)
 }
 
 /// detect wallpaper for noctalia shell
-fn detect_noctalia() -> Option<String> {
+fn detect_noctalia(output: Option<&str>) -> Option<String> {
     #[derive(Debug, serde::Deserialize)]
     pub struct NoctaliaState {
         pub state: NoctaliaWallpaperState,
@@ -133,7 +564,7 @@ fn detect_noctalia() -> Option<String> {
         pub wallpapers: std::collections::HashMap<String, String>,
     }
 
-    fn try_noctalia_command(cmd: &mut Command) -> Option<String> {
+    fn try_noctalia_command(cmd: &mut Command, monitor: Option<&str>) -> Option<String> {
         let output = cmd.output().ok()?;
 
         if !output.status.success() {
@@ -143,17 +574,23 @@ fn detect_noctalia() -> Option<String> {
         let output_str = String::from_utf8_lossy(&output.stdout);
         let noctalia_state: NoctaliaState = serde_json::from_str(&output_str).ok()?;
 
-        noctalia_state.state.wallpapers.values().next().cloned()
+        let mut wallpapers: Vec<_> = noctalia_state.state.wallpapers.into_iter().collect();
+        wallpapers.sort();
+        for_output(wallpapers, monitor)
     }
 
-    try_noctalia_command(Command::new("noctalia-shell").args(["ipc", "call", "state", "all"]))
-        .or_else(|| {
-            try_noctalia_command(
-                Command::new("qs")
-                    .args(["-c", "noctalia-shell"])
-                    .args(["ipc", "call", "state", "all"]),
-            )
-        })
+    try_noctalia_command(
+        Command::new("noctalia-shell").args(["ipc", "call", "state", "all"]),
+        output,
+    )
+    .or_else(|| {
+        try_noctalia_command(
+            Command::new("qs")
+                .args(["-c", "noctalia-shell"])
+                .args(["ipc", "call", "state", "all"]),
+            output,
+        )
+    })
 }
 
 /// detect wallpaper for dank material shell
@@ -175,26 +612,211 @@ pub fn detect<P>(wallpaper_arg: &Option<P>) -> Option<String>
 where
     P: AsRef<Path>,
 {
-    [
+    // multi output backends use the wallpaper of the focused output, which is only
+    // looked up once a backend needs it
+    let focused = OnceCell::new();
+    let output = || focused.get_or_init(output::focused_name).as_deref();
+
+    // backends are tried in order until one returns a wallpaper that exists
+    let backends: [&dyn Fn() -> Option<String>; 14] = [
         // wallpaper provided in arguments
-        wallpaper_arg
-            .as_ref()
-            .and_then(|s| s.as_ref().to_str().map(std::string::ToString::to_string)),
-        detect_iynaixos(),
-        detect_swww(),
-        detect_swaybg(),
-        detect_hyprpaper(),
-        detect_noctalia(),
-        detect_dms(),
-        detect_gsettings(), // gnome / cinnamon / mate
-        detect_plasma(),    // kde
-    ]
-    .iter()
-    .find(|&wallpaper| {
-        if let Some(wall) = wallpaper {
-            return PathBuf::from(wall).exists();
+        &|| {
+            wallpaper_arg
+                .as_ref()
+                .and_then(|s| s.as_ref().to_str().map(std::string::ToString::to_string))
+        },
+        &detect_iynaixos,
+        &|| detect_swww(output()),
+        &|| detect_swaybg(output()),
+        &|| detect_hyprpaper(output()),
+        &detect_feh,
+        &detect_nitrogen,
+        &|| detect_noctalia(output()),
+        &detect_dms,
+        &detect_budgie,
+        &|| detect_xfce(output()),
+        &detect_lxqt,
+        &detect_gsettings, // gnome / cinnamon / mate
+        &detect_plasma,    // kde
+    ];
+
+    backends
+        .iter()
+        .find_map(|detect| detect().filter(|wall| PathBuf::from(wall).exists()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// parses a hyprpaper config from files written to a temporary directory
+    fn hyprpaper(name: &str, files: &[(&str, &str)]) -> Vec<(String, String)> {
+        let dir = std::env::temp_dir().join(format!("wfetch-{name}-{}", std::process::id()));
+        for (file, contents) in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().expect("invalid test path"))
+                .expect("could not create test directory");
+            std::fs::write(path, contents).expect("could not write test config");
         }
-        false
-    })
-    .and_then(std::clone::Clone::clone)
+
+        let mut wallpapers = Vec::new();
+        hyprpaper_config(
+            &dir.join(files[0].0),
+            &mut HashMap::new(),
+            &mut wallpapers,
+            0,
+        );
+        std::fs::remove_dir_all(&dir).expect("could not remove test directory");
+        wallpapers
+    }
+
+    fn wallpaper(monitor: &str, path: &str) -> (String, String) {
+        (monitor.to_string(), path.to_string())
+    }
+
+    #[test]
+    fn hyprpaper_config_blocks() {
+        let config = "
+            $walls = /walls # wallpaper directory
+            $wall = unused
+
+            wallpaper {
+                monitor = DP-1
+                path = $walls/a##b.png
+                fit_mode = cover
+            }
+
+            # wallpaper {
+            wallpaper {
+                monitor =
+                path = /walls/default.png
+            }
+
+            wallpaper {
+                monitor = HDMI-A-1
+            }
+        ";
+
+        assert_eq!(
+            hyprpaper("blocks", &[("hyprpaper.conf", config)]),
+            [
+                wallpaper("DP-1", "/walls/a#b.png"),
+                wallpaper("", "/walls/default.png")
+            ]
+        );
+    }
+
+    #[test]
+    fn hyprpaper_config_old_syntax() {
+        let config = "
+            preload = /walls/a.png
+            wallpaper = DP-1, contain:/walls/a.png
+            wallpaper = ,/walls/b.png
+        ";
+
+        assert_eq!(
+            hyprpaper("old", &[("hyprpaper.conf", config)]),
+            [
+                wallpaper("DP-1", "/walls/a.png"),
+                wallpaper("", "/walls/b.png")
+            ]
+        );
+    }
+
+    #[test]
+    fn hyprpaper_config_source() {
+        let files = [
+            (
+                "hyprpaper.conf",
+                "$walls = /walls\nsource = ./conf/monitors.conf\nwallpaper = DP-2,$walls/c.png",
+            ),
+            // relative to the config that sources it
+            ("conf/monitors.conf", "source = nested.conf"),
+            (
+                "conf/nested.conf",
+                "wallpaper {\nmonitor = DP-1\npath = $walls/a.png\n}",
+            ),
+        ];
+
+        assert_eq!(
+            hyprpaper("source", &files),
+            [
+                wallpaper("DP-1", "/walls/a.png"),
+                wallpaper("DP-2", "/walls/c.png")
+            ]
+        );
+    }
+
+    #[test]
+    fn ini_sections_appletsrc() {
+        let config = "
+            ignored=before any section
+
+            [Containments][1]
+            activityId=abc
+            wallpaperplugin = org.kde.image
+
+            [Containments][1][Wallpaper][org.kde.image][General]
+            Image=file:///walls/a=b.png
+            [Containments][2]
+        ";
+
+        let sections = ini_sections(config);
+        let headers: Vec<_> = sections.iter().map(|(header, _)| header.as_str()).collect();
+        assert_eq!(
+            headers,
+            [
+                "[Containments][1]",
+                "[Containments][1][Wallpaper][org.kde.image][General]",
+                "[Containments][2]"
+            ]
+        );
+
+        assert_eq!(
+            sections[0].1.get("wallpaperplugin").map(String::as_str),
+            Some("org.kde.image")
+        );
+        assert_eq!(
+            sections[0].1.get("activityId").map(String::as_str),
+            Some("abc")
+        );
+        // only the first `=` separates the key and value
+        assert_eq!(
+            sections[1].1.get("Image").map(String::as_str),
+            Some("file:///walls/a=b.png")
+        );
+        assert!(sections[2].1.is_empty());
+    }
+
+    #[test]
+    fn shell_words_fehbg() {
+        assert_eq!(
+            shell_words("feh --no-fehbg --bg-fill '/home/user/My Wallpapers/wall.png' "),
+            [
+                "feh",
+                "--no-fehbg",
+                "--bg-fill",
+                "/home/user/My Wallpapers/wall.png"
+            ]
+        );
+        assert_eq!(
+            shell_words(r#"/usr/bin/feh --bg-scale "/tmp/a \"b\".jpg" /tmp/c\ d.jpg"#),
+            [
+                "/usr/bin/feh",
+                "--bg-scale",
+                r#"/tmp/a "b".jpg"#,
+                "/tmp/c d.jpg"
+            ]
+        );
+    }
+
+    #[test]
+    fn shell_words_quotes() {
+        // backslashes are literal in single quotes
+        assert_eq!(shell_words(r"'a\b' c"), [r"a\b", "c"]);
+        // adjacent quoted parts are a single word
+        assert_eq!(shell_words(r#"a'b c'"d""#), ["ab cd"]);
+        assert_eq!(shell_words("feh ''"), ["feh", ""]);
+        assert!(shell_words("  ").is_empty());
+    }
 }